@@ -0,0 +1,66 @@
+//! Measures the per-request overhead of the client against a mock returning immediately.
+//! Compares a client using the concrete `MockClient` with one using the type erased `BoxedHttpClient`,
+//! which boxes every request future.
+//!
+//! Run with `cargo bench --bench overhead`.
+use std::time::{Duration, Instant};
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    errors::DomeneshopError,
+    http::{BoxedHttpClient, HttpClient},
+    http_client::mock::MockClient,
+};
+use http_types::{Request, Response, StatusCode};
+
+const ITERATIONS: u32 = 100_000;
+
+async fn return_empty_list(_: Request) -> Result<Response, DomeneshopError> {
+    let mut response = Response::new(StatusCode::Ok);
+    response.set_body("[]");
+    Ok(response)
+}
+
+fn create_client<H: HttpClient>(http_client: H) -> DomeneshopClient<H> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        http_client,
+        DomeneshopClientConfiguration::default(),
+    )
+    .unwrap()
+}
+
+fn measure<H: HttpClient>(client: &DomeneshopClient<H>) -> Duration {
+    tokio_test::block_on(async {
+        for _ in 0..ITERATIONS / 10 {
+            client.list_domains().await.unwrap();
+        }
+        let started = Instant::now();
+        for _ in 0..ITERATIONS {
+            client.list_domains().await.unwrap();
+        }
+        started.elapsed()
+    })
+}
+
+fn report(name: &str, elapsed: Duration) {
+    println!(
+        "{:<10} {:>8} ns/request",
+        name,
+        elapsed.as_nanos() / u128::from(ITERATIONS)
+    );
+}
+
+fn main() {
+    let mock = MockClient {
+        req_received: return_empty_list,
+    };
+    let generic = create_client(MockClient {
+        req_received: return_empty_list,
+    });
+    let boxed = create_client(BoxedHttpClient::new(mock));
+
+    report("generic", measure(&generic));
+    report("boxed", measure(&boxed));
+}
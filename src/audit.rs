@@ -0,0 +1,398 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use http_types::{Method, Request, Response};
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    cache::api_segments,
+    endpoints::{dns::DnsId, domains::DomainId},
+    errors::DomeneshopError,
+};
+
+/// The mutating endpoints that are audited
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEndpoint {
+    /// `add_dns_record`
+    AddDnsRecord,
+    /// `update_dns_record`
+    UpdateDnsRecord,
+    /// `delete_dns_record`
+    DeleteDnsRecord,
+    /// `add_forward`
+    AddForward,
+    /// `update_forward`
+    UpdateForward,
+    /// `delete_forward`
+    DeleteForward,
+    /// `update_dyndns`
+    UpdateDyndns,
+}
+
+/// The outcome of an audited call
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// The API accepted the change
+    Success,
+    /// The change was recorded by a [`DryRun`](crate::dry_run::DryRun) and never sent
+    DryRun,
+    /// The call failed. The change may or may not have been applied if the error is a transport error.
+    Failure {
+        /// The status code returned by the API, if any
+        status_code: Option<u16>,
+        /// Description of the error
+        message: String,
+    },
+}
+
+/// A mutating call made through the client
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct AuditEvent {
+    /// When the call completed
+    pub timestamp: DateTime<Utc>,
+    /// The actor given to the [`AuditLog`]
+    pub actor: Option<String>,
+    /// The endpoint called
+    pub endpoint: AuditEndpoint,
+    /// The domain changed. Not known for `update_dyndns`.
+    pub domain_id: Option<DomainId>,
+    /// The DNS record changed. For added records, this is the id assigned by the API.
+    pub record_id: Option<DnsId>,
+    /// The host of the forward changed
+    pub forward_host: Option<String>,
+    /// The hostname updated through `update_dyndns`
+    pub hostname: Option<String>,
+    /// The record or forward before the change.
+    /// Only set if enabled through [`capture_before`](AuditLog::capture_before).
+    pub before: Option<Value>,
+    /// The record or forward sent to the API
+    pub after: Option<Value>,
+    /// The outcome of the call
+    pub outcome: AuditOutcome,
+}
+
+/// Receives an [`AuditEvent`] for every mutating call made through the client.
+///
+/// The sink is called after the call completes, also when it fails.
+/// Errors while storing the event cannot fail the call. They are counted by [`AuditLog::failed_events`],
+/// and reported through `tracing` if the `tracing`-feature is enabled.
+pub trait AuditSink: Send + Sync {
+    /// Stores the event
+    fn record(&self, event: &AuditEvent) -> Result<(), DomeneshopError>;
+}
+
+/// An [`AuditSink`] appending every event as a line of JSON to a file.
+///
+/// Each event is written and flushed before the call returns.
+pub struct JsonLinesSink {
+    file: Mutex<File>,
+}
+
+impl JsonLinesSink {
+    /// Opens the file for appending, creating it if it does not exist
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, DomeneshopError> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| {
+                DomeneshopError::configuration(format!(
+                    "Failed to open audit log {}",
+                    path.display()
+                ))
+                .with_source(err)
+            })?;
+        Ok(JsonLinesSink {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for JsonLinesSink {
+    fn record(&self, event: &AuditEvent) -> Result<(), DomeneshopError> {
+        let mut line = serde_json::to_vec(event).map_err(|err| {
+            DomeneshopError::new("Failed to serialize audit event").with_source(err)
+        })?;
+        line.push(b'\n');
+        let mut file = self.file.lock().unwrap();
+        file.write_all(&line)
+            .and_then(|_| file.flush())
+            .map_err(|err| DomeneshopError::new("Failed to write audit event").with_source(err))
+    }
+}
+
+/// Sends an [`AuditEvent`] to a sink for every mutating call made through the client.
+///
+/// # Example
+/// ```no_run
+/// use domeneshop_client::audit::{AuditLog, JsonLinesSink};
+///
+/// # fn main() -> Result<(), domeneshop_client::errors::DomeneshopError> {
+/// let audit = AuditLog::new(JsonLinesSink::new("audit.jsonl")?)
+///     .actor("migration-script")
+///     .capture_before(true);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AuditLog {
+    sink: Arc<dyn AuditSink>,
+    actor: Option<String>,
+    capture_before: bool,
+    failed_events: Arc<AtomicU64>,
+}
+
+impl AuditLog {
+    /// Creates an audit log sending events to the sink
+    pub fn new(sink: impl AuditSink + 'static) -> Self {
+        AuditLog {
+            sink: Arc::new(sink),
+            actor: None,
+            capture_before: false,
+            failed_events: Arc::default(),
+        }
+    }
+
+    /// Labels every event with the actor, e.g. a user or the name of a script
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Fetches the record or forward before updating or deleting it, to include it in the event.
+    /// This costs an extra request per update and delete. Disabled by default.
+    pub fn capture_before(mut self, capture_before: bool) -> Self {
+        self.capture_before = capture_before;
+        self
+    }
+
+    /// The number of events the sink failed to store. Shared by all clones of the log.
+    pub fn failed_events(&self) -> u64 {
+        self.failed_events.load(Ordering::Relaxed)
+    }
+
+    /// Starts an event if the request is a mutation.
+    /// Returns the event and the url to fetch the state before the change from, if enabled.
+    pub(crate) async fn begin(
+        &self,
+        request: &mut Request,
+    ) -> Result<Option<(AuditEvent, Option<Url>)>, DomeneshopError> {
+        let url = request.url().clone();
+        let segments = api_segments(&url);
+        let method = request.method();
+        let domain_id = || segments.get(1).and_then(|id| id.parse().ok());
+
+        let endpoint = match (method, segments.as_slice()) {
+            (Method::Get, ["dyndns", "update"]) => AuditEndpoint::UpdateDyndns,
+            (Method::Post, ["domains", _, "dns"]) => AuditEndpoint::AddDnsRecord,
+            (Method::Put, ["domains", _, "dns", _]) => AuditEndpoint::UpdateDnsRecord,
+            (Method::Delete, ["domains", _, "dns", _]) => AuditEndpoint::DeleteDnsRecord,
+            (Method::Post, ["domains", _, "forwards"]) => AuditEndpoint::AddForward,
+            (Method::Put, ["domains", _, "forwards", _]) => AuditEndpoint::UpdateForward,
+            (Method::Delete, ["domains", _, "forwards", _]) => AuditEndpoint::DeleteForward,
+            _ => return Ok(None),
+        };
+        let mut event = AuditEvent {
+            timestamp: Utc::now(),
+            actor: self.actor.clone(),
+            endpoint,
+            domain_id: None,
+            record_id: None,
+            forward_host: None,
+            hostname: None,
+            before: None,
+            after: None,
+            outcome: AuditOutcome::Success,
+        };
+        if endpoint == AuditEndpoint::UpdateDyndns {
+            let query: Vec<_> = url.query_pairs().collect();
+            let value = |name: &str| {
+                query
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.to_string())
+            };
+            event.hostname = value("hostname");
+            event.after = value("myip").map(|ip| serde_json::json!({ "myip": ip }));
+            return Ok(Some((event, None)));
+        }
+        event.domain_id = domain_id();
+        match segments.as_slice() {
+            [_, _, "dns", id] => event.record_id = id.parse().ok(),
+            [_, _, "forwards", host] => event.forward_host = Some(host.to_string()),
+            _ => {}
+        }
+
+        let body = request.take_body().into_bytes().await.map_err(|err| {
+            DomeneshopError::new("Failed to read request body").with_source(err.into_inner())
+        })?;
+        if !body.is_empty() {
+            event.after = serde_json::from_slice(&body).ok();
+            request.set_body(body);
+        }
+        if event.endpoint == AuditEndpoint::AddForward {
+            event.forward_host = event
+                .after
+                .as_ref()
+                .and_then(|after| after.get("host"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+        }
+
+        let before_url = match event.endpoint {
+            AuditEndpoint::UpdateDnsRecord
+            | AuditEndpoint::DeleteDnsRecord
+            | AuditEndpoint::UpdateForward
+            | AuditEndpoint::DeleteForward
+                if self.capture_before =>
+            {
+                Some(url)
+            }
+            _ => None,
+        };
+        Ok(Some((event, before_url)))
+    }
+
+    /// Completes the event with the outcome of the call and sends it to the sink
+    pub(crate) async fn finish(
+        &self,
+        mut event: AuditEvent,
+        result: &mut Result<Response, DomeneshopError>,
+        dry_run: bool,
+    ) {
+        event.timestamp = Utc::now();
+        event.outcome = match result {
+            Ok(_) if dry_run => AuditOutcome::DryRun,
+            Ok(_) => AuditOutcome::Success,
+            Err(err) => AuditOutcome::Failure {
+                status_code: err.status().map(u16::from),
+                message: err.to_string(),
+            },
+        };
+        if let (Ok(response), AuditEndpoint::AddDnsRecord) = (result, event.endpoint) {
+            if let Ok(body) = response.take_body().into_bytes().await {
+                event.record_id = serde_json::from_slice::<Value>(&body)
+                    .ok()
+                    .and_then(|body| body.get("id")?.as_i64())
+                    .and_then(|id| DnsId::try_from(id).ok());
+                response.set_body(body);
+            }
+        }
+        let result = self.sink.record(&event);
+        if result.is_err() {
+            self.failed_events.fetch_add(1, Ordering::Relaxed);
+        }
+        #[cfg(feature = "tracing")]
+        if let Err(err) = &result {
+            tracing::error!(error = %err, "Failed to record audit event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_types::{Method, Request, Response, StatusCode};
+
+    use crate::errors::DomeneshopError;
+
+    use super::{AuditEndpoint, AuditEvent, AuditLog, AuditOutcome, AuditSink};
+
+    struct NoopSink;
+
+    impl AuditSink for NoopSink {
+        fn record(&self, _: &AuditEvent) -> Result<(), DomeneshopError> {
+            Ok(())
+        }
+    }
+
+    fn request(method: Method, path: &str) -> Request {
+        Request::new(
+            method,
+            format!("https://api.domeneshop.no/v0{}", path).as_str(),
+        )
+    }
+
+    fn begin(log: &AuditLog, request: &mut Request) -> Option<(AuditEvent, bool)> {
+        tokio_test::block_on(log.begin(request))
+            .unwrap()
+            .map(|(event, before_url)| (event, before_url.is_some()))
+    }
+
+    #[test]
+    fn reads_are_not_audited() {
+        let log = AuditLog::new(NoopSink);
+
+        assert!(begin(&log, &mut request(Method::Get, "/domains/1/dns/2")).is_none());
+        assert!(begin(&log, &mut request(Method::Get, "/domains")).is_none());
+    }
+
+    #[test]
+    fn begin_identifies_target() {
+        let log = AuditLog::new(NoopSink).actor("alice").capture_before(true);
+        let mut add = request(Method::Post, "/domains/1/forwards");
+        add.set_body(r#"{"host":"www","frame":false,"url":"https://example.com"}"#);
+
+        let (update, update_before) =
+            begin(&log, &mut request(Method::Put, "/domains/1/dns/2")).unwrap();
+        let (add, add_before) = begin(&log, &mut add).unwrap();
+        let (dyndns, _) = begin(
+            &log,
+            &mut request(Method::Get, "/dyndns/update?hostname=www.example.com"),
+        )
+        .unwrap();
+
+        assert_eq!(update.endpoint, AuditEndpoint::UpdateDnsRecord);
+        assert_eq!(update.actor.as_deref(), Some("alice"));
+        assert_eq!((update.domain_id, update.record_id), (Some(1), Some(2)));
+        assert!(update_before);
+        assert_eq!(add.endpoint, AuditEndpoint::AddForward);
+        assert_eq!(add.forward_host.as_deref(), Some("www"));
+        assert!(!add_before);
+        assert_eq!(dyndns.hostname.as_deref(), Some("www.example.com"));
+        assert_eq!(dyndns.domain_id, None);
+    }
+
+    #[test]
+    fn finish_keeps_response_body() {
+        let log = AuditLog::new(NoopSink);
+        let (event, _) = begin(&log, &mut request(Method::Post, "/domains/1/dns")).unwrap();
+        let mut response = Response::new(StatusCode::Created);
+        response.set_body(r#"{"id": 5}"#);
+        let mut result = Ok(response);
+
+        tokio_test::block_on(log.finish(event, &mut result, false));
+
+        let body = tokio_test::block_on(result.unwrap().body_string()).unwrap();
+        assert_eq!(body, r#"{"id": 5}"#);
+    }
+
+    #[test]
+    fn outcome_is_serialized_with_status() {
+        let failure = AuditOutcome::Failure {
+            status_code: Some(404),
+            message: "Not found".to_string(),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&failure).unwrap(),
+            r#"{"status":"failure","status_code":404,"message":"Not found"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&AuditOutcome::DryRun).unwrap(),
+            r#"{"status":"dry_run"}"#
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Local emulator of the Domeneshop API.
+//!
+//! Prints `Listening on http://<address>` once the server accepts connections.
+use std::{io::Write, net::TcpListener, process::ExitCode};
+
+use domeneshop_client::{
+    client::{SECRET_ENV, TOKEN_ENV},
+    emulator::Emulator,
+    http_client::fake::{FakeDomeneshop, FakeState},
+};
+
+const USAGE: &str =
+    "Usage: domeneshop-emulator [--bind ADDRESS] [--fixture FILE] [--token TOKEN] [--secret SECRET]
+
+Serves the Domeneshop API on ADDRESS (default 127.0.0.1:8080), seeded with the state in FILE.
+The credentials default to the DOMENESHOP_TOKEN and DOMENESHOP_SECRET environment variables.";
+
+struct Options {
+    bind: String,
+    fixture: Option<String>,
+    token: Option<String>,
+    secret: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        bind: "127.0.0.1:8080".to_string(),
+        fixture: None,
+        token: std::env::var(TOKEN_ENV).ok(),
+        secret: std::env::var(SECRET_ENV).ok(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--bind" => options.bind = value()?,
+            "--fixture" => options.fixture = Some(value()?),
+            "--token" => options.token = Some(value()?),
+            "--secret" => options.secret = Some(value()?),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let options = match parse_args(args.into_iter()) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let (Some(token), Some(secret)) = (options.token, options.secret) else {
+        eprintln!("Missing credentials\n\n{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let state = match options.fixture {
+        Some(path) => match FakeState::load(path) {
+            Ok(state) => state,
+            Err(err) => {
+                eprintln!("{}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => FakeState::default(),
+    };
+    let fake = FakeDomeneshop::from_state(state).with_credentials(token, secret);
+
+    let listener = match TcpListener::bind(&options.bind) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Could not bind to {}: {}", options.bind, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    match listener.local_addr() {
+        Ok(address) => println!("Listening on http://{}", address),
+        Err(err) => {
+            eprintln!("Could not read local address: {}", err);
+            return ExitCode::FAILURE;
+        }
+    }
+    _ = std::io::stdout().flush();
+
+    let shutdown = async {
+        _ = tokio::signal::ctrl_c().await;
+    };
+    match Emulator::new(fake)
+        .serve_with_shutdown(listener, shutdown)
+        .await
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use std::{net::IpAddr, path::Path};
+
+use futures_lite::future::block_on;
+use http_types::{Method, Request, Response};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    audit::AuditLog,
+    cache::ResponseCache,
+    client::{self, loader},
+    credentials::Credentials,
+    dry_run::DryRun,
+    endpoints::{
+        dns::{AddDnsRecordResponse, DnsId, DnsRecordData, DnsType, ExistingDnsRecord},
+        domains::{Domain, DomainId},
+        forwards::HttpForward,
+        invoices::{Invoice, InvoiceId, InvoiceStatus},
+    },
+    errors::DomeneshopError,
+    http::HttpClient,
+    layer::Layer,
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+    zone::{ImportPlan, ImportReport},
+};
+
+/// A trait used to abstract the blocking transport mechanism for sending requests.
+///
+/// This is the blocking equivalent of [`HttpClient`].
+/// With the `reqwest`-feature enabled, it is implemented for [`reqwest::blocking::Client`].
+///
+/// # Example
+/// ```
+/// use http_types::{Request, Response};
+/// use domeneshop_client::{blocking::BlockingHttpClient, errors::DomeneshopError};
+///
+/// struct MyClient;
+///
+/// impl BlockingHttpClient for MyClient {
+///     fn execute_request(&self, request: Request) -> Result<Response, DomeneshopError> {
+///         todo!();
+///     }
+/// }
+/// ```
+pub trait BlockingHttpClient {
+    /// Executes a request, blocking the current thread until the response is received
+    fn execute_request(&self, request: Request) -> Result<Response, DomeneshopError>;
+}
+
+/// Configuration of the created blocking client.
+/// See [`DomeneshopClientConfiguration`](crate::client::DomeneshopClientConfiguration) for a description of the settings.
+pub struct DomeneshopClientConfiguration {
+    /// Overrides default user agent-header if set
+    pub user_agent: Option<String>,
+    /// Overrides default base url if set
+    pub base_url: Option<String>,
+    /// Decides if and how failed requests are retried.
+    pub retry_policy: RetryPolicy,
+    /// Optional client-side rate limiter. Disabled if not set.
+    pub rate_limiter: Option<RateLimiter>,
+    /// Optional cache of responses from read endpoints. Disabled if not set.
+    pub cache: Option<ResponseCache>,
+    /// Records mutations instead of sending them if set.
+    pub dry_run: Option<DryRun>,
+    /// Optional audit log receiving an event for every mutating call. Disabled if not set.
+    pub audit: Option<AuditLog>,
+    /// Layers wrapping the underlying client, invoked in the given order for every request.
+    pub layers: Vec<Box<dyn Layer>>,
+    /// Sets an optional underlying client.
+    /// Without `reqwest`-feature enabled, this is NOT optional, and creating the client fails if it is not set.
+    pub underlying_client: Option<Box<dyn BlockingHttpClient + Send + Sync + 'static>>,
+}
+
+impl Default for DomeneshopClientConfiguration {
+    /// Creates a default configuration for the blocking domeneshop client
+    fn default() -> DomeneshopClientConfiguration {
+        DomeneshopClientConfiguration {
+            user_agent: None,
+            base_url: None,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            cache: None,
+            dry_run: None,
+            audit: None,
+            layers: Vec::new(),
+            underlying_client: None,
+        }
+    }
+}
+
+/// The blocking client used to interact with the domeneshop API.
+///
+/// Mirrors every operation of the async [`DomeneshopClient`](crate::client::DomeneshopClient),
+/// but blocks the current thread until the operation is complete.
+/// Do not use this client from within an async runtime.
+/// Like the async client, it is cheap to clone and can be shared between threads.
+///
+/// # Example
+/// ```no_run
+/// use domeneshop_client::blocking::{ DomeneshopClient, DomeneshopClientConfiguration };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = DomeneshopClient::new(
+///     String::from("token"),
+///     String::from("secret"),
+///     DomeneshopClientConfiguration::default(),
+/// )?;
+/// let domains = client.list_domains()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DomeneshopClient {
+    inner: client::DomeneshopClient<BlockingAdapter>,
+}
+
+struct BlockingAdapter(Box<dyn BlockingHttpClient + Send + Sync>);
+
+impl HttpClient for BlockingAdapter {
+    async fn execute_request(&self, request: Request) -> Result<Response, DomeneshopError> {
+        self.0.execute_request(request)
+    }
+}
+
+impl DomeneshopClient {
+    /// Creates a new blocking domeneshop client
+    pub fn new(
+        token: String,
+        secret: String,
+        configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        DomeneshopClient::with_credentials(Credentials::new(token, secret), configuration)
+    }
+
+    /// Creates a new blocking domeneshop client using the given credentials
+    pub fn with_credentials(
+        credentials: Credentials,
+        configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        let underlying_client = create_client(configuration.underlying_client)?;
+        let inner = client::DomeneshopClient::with_http_client(
+            credentials,
+            underlying_client,
+            client::DomeneshopClientConfiguration {
+                user_agent: configuration.user_agent,
+                base_url: configuration.base_url,
+                retry_policy: configuration.retry_policy,
+                rate_limiter: configuration.rate_limiter,
+                cache: configuration.cache,
+                dry_run: configuration.dry_run,
+                audit: configuration.audit,
+                layers: configuration.layers,
+            },
+        )?;
+        Ok(DomeneshopClient { inner })
+    }
+
+    /// Creates a new blocking client using credentials from the environment.
+    /// See [`from_env`](crate::client::DomeneshopClient::from_env) for the variables used.
+    pub fn from_env() -> Result<Self, DomeneshopError> {
+        DomeneshopClient::from_env_with_configuration(DomeneshopClientConfiguration::default())
+    }
+
+    /// Creates a new blocking client using credentials from the environment.
+    /// `DOMENESHOP_BASE_URL` is only used if the configuration does not set a base url.
+    pub fn from_env_with_configuration(
+        mut configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        let settings = loader::load_env(|key| std::env::var(key).ok())?;
+        configuration.base_url = settings.base_url_or(configuration.base_url.take());
+        DomeneshopClient::with_credentials(settings.credentials, configuration)
+    }
+
+    /// Creates a new blocking client using credentials from the given profile in the default config file.
+    /// See [`from_config_file`](crate::client::DomeneshopClient::from_config_file) for the format and location of the file.
+    pub fn from_config_file(profile: &str) -> Result<Self, DomeneshopError> {
+        DomeneshopClient::from_config_file_with_configuration(
+            loader::default_config_path()?,
+            profile,
+            DomeneshopClientConfiguration::default(),
+        )
+    }
+
+    /// Creates a new blocking client using credentials from the given profile in the config file at `path`.
+    /// The `base_url` of the profile is only used if the configuration does not set a base url.
+    pub fn from_config_file_with_configuration(
+        path: impl AsRef<Path>,
+        profile: &str,
+        mut configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        let settings = loader::load_config_file(path.as_ref(), profile)?;
+        configuration.base_url = settings.base_url_or(configuration.base_url.take());
+        DomeneshopClient::with_credentials(settings.credentials, configuration)
+    }
+
+    /// Sends a request to an endpoint without a dedicated operation, deserializing the JSON-response.
+    /// See [`request`](crate::client::DomeneshopClient::request).
+    pub fn request<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<T, DomeneshopError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        block_on(self.inner.request(method, path, query, body))
+    }
+
+    /// Sends a request to an endpoint without a dedicated operation, returning the response as is.
+    /// See [`request_raw`](crate::client::DomeneshopClient::request_raw).
+    pub fn request_raw<B>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<Response, DomeneshopError>
+    where
+        B: Serialize + ?Sized,
+    {
+        block_on(self.inner.request_raw(method, path, query, body))
+    }
+
+    /// Get DNS Record by id
+    pub fn get_dns_record(
+        &self,
+        domain_id: DomainId,
+        dns_id: DnsId,
+    ) -> Result<ExistingDnsRecord, DomeneshopError> {
+        block_on(self.inner.get_dns_record(domain_id, dns_id))
+    }
+
+    /// Lists all DNS records for a domain
+    pub fn list_dns_records(
+        &self,
+        domain_id: DomainId,
+    ) -> Result<Vec<ExistingDnsRecord>, DomeneshopError> {
+        block_on(self.inner.list_dns_records(domain_id))
+    }
+
+    /// Lists filtered subset of DNS records for a domain
+    pub fn list_dns_records_with_filter(
+        &self,
+        domain_id: DomainId,
+        host_filter: Option<String>,
+        type_filter: Option<DnsType>,
+    ) -> Result<Vec<ExistingDnsRecord>, DomeneshopError> {
+        block_on(
+            self.inner
+                .list_dns_records_with_filter(domain_id, host_filter, type_filter),
+        )
+    }
+
+    /// adds a new  DNS record for the given domain
+    pub fn add_dns_record(
+        &self,
+        domain_id: DomainId,
+        record: DnsRecordData,
+    ) -> Result<AddDnsRecordResponse, DomeneshopError> {
+        block_on(self.inner.add_dns_record(domain_id, record))
+    }
+
+    /// Updates an existing DNS record for the given domain
+    pub fn update_dns_record(
+        &self,
+        domain_id: DomainId,
+        record: ExistingDnsRecord,
+    ) -> Result<(), DomeneshopError> {
+        block_on(self.inner.update_dns_record(domain_id, record))
+    }
+
+    /// Deletes a dns record using the given id
+    pub fn delete_dns_record(
+        &self,
+        domain_id: DomainId,
+        dns_id: DnsId,
+    ) -> Result<(), DomeneshopError> {
+        block_on(self.inner.delete_dns_record(domain_id, dns_id))
+    }
+
+    /// Exports all DNS records of a domain as an RFC 1035 zone file.
+    ///
+    /// See [`render_zone`](crate::zone::render_zone) for the format.
+    pub fn export_zone(&self, domain_id: DomainId) -> Result<String, DomeneshopError> {
+        block_on(self.inner.export_zone(domain_id))
+    }
+
+    /// Previews importing records to a domain, e.g. the records of [`parse_zone`](crate::zone::parse_zone).
+    ///
+    /// Nothing is changed. Pass the plan to [`import_zone`](DomeneshopClient::import_zone) to add the records.
+    pub fn preview_zone_import(
+        &self,
+        domain_id: DomainId,
+        records: Vec<DnsRecordData>,
+    ) -> Result<ImportPlan, DomeneshopError> {
+        block_on(self.inner.preview_zone_import(domain_id, records))
+    }
+
+    /// Adds the records of a plan from [`preview_zone_import`](DomeneshopClient::preview_zone_import).
+    /// A failing record does not stop the import, and the report contains the outcome of every record.
+    pub fn import_zone(&self, plan: ImportPlan) -> ImportReport {
+        block_on(self.inner.import_zone(plan))
+    }
+
+    /// Removes all cached responses of a domain. Does nothing if the client has no [`ResponseCache`].
+    pub fn invalidate_domain(&self, domain_id: DomainId) {
+        self.inner.invalidate_domain(domain_id)
+    }
+
+    /// Gets a single domain by id
+    pub fn get_domain(&self, id: DomainId) -> Result<Domain, DomeneshopError> {
+        block_on(self.inner.get_domain(id))
+    }
+
+    /// Lists all domains for the current user
+    pub fn list_domains(&self) -> Result<Vec<Domain>, DomeneshopError> {
+        block_on(self.inner.list_domains())
+    }
+
+    /// Lists all domain for the current user.
+    /// Only returns domains containing the text in `filter`.
+    pub fn list_domains_with_filter(
+        &self,
+        filter: impl AsRef<str>,
+    ) -> Result<Vec<Domain>, DomeneshopError> {
+        block_on(self.inner.list_domains_with_filter(filter))
+    }
+
+    /// Attempts to get an existing forward by host.
+    /// Subdomain of the forward, `@` for the root domain
+    pub fn get_forward(
+        &self,
+        id: DomainId,
+        host: String,
+    ) -> Result<Option<HttpForward>, DomeneshopError> {
+        block_on(self.inner.get_forward(id, host))
+    }
+
+    /// List all forwards for the specified domain.
+    pub fn list_forwards(&self, id: DomainId) -> Result<Vec<HttpForward>, DomeneshopError> {
+        block_on(self.inner.list_forwards(id))
+    }
+
+    /// Create a forwarding for the specified domain, to a given URL.
+    pub fn add_forward(&self, id: DomainId, forward: HttpForward) -> Result<(), DomeneshopError> {
+        block_on(self.inner.add_forward(id, forward))
+    }
+
+    /// Update a forwarding for the specified domain, to a given URL.
+    pub fn update_forward(
+        &self,
+        id: DomainId,
+        forward: HttpForward,
+    ) -> Result<(), DomeneshopError> {
+        block_on(self.inner.update_forward(id, forward))
+    }
+
+    /// Deletes forward by host
+    pub fn delete_forward(&self, domain_id: DomainId, host: String) -> Result<(), DomeneshopError> {
+        block_on(self.inner.delete_forward(domain_id, host))
+    }
+
+    /// Get invoice by invoice number
+    pub fn get_invoice(&self, id: InvoiceId) -> Result<Option<Invoice>, DomeneshopError> {
+        block_on(self.inner.get_invoice(id))
+    }
+
+    /// List invoices for your account. Only invoices from the past 3 years are returned.
+    pub fn list_invoices(&self) -> Result<Vec<Invoice>, DomeneshopError> {
+        block_on(self.inner.list_invoices())
+    }
+
+    /// Lists all the invoices for your account, filtered on a specific status
+    pub fn list_invoices_with_status(
+        &self,
+        status: InvoiceStatus,
+    ) -> Result<Vec<Invoice>, DomeneshopError> {
+        block_on(self.inner.list_invoices_with_status(status))
+    }
+
+    /// Update DNS using the "IP update protocol".
+    /// If `ip` is not provided, the IP of the client making the API request will be used.
+    pub fn update_dyndns<S>(&self, hostname: S, ip: Option<IpAddr>) -> Result<(), DomeneshopError>
+    where
+        S: Into<String>,
+    {
+        block_on(self.inner.update_dyndns(hostname, ip))
+    }
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn create_client(
+    client: Option<Box<dyn BlockingHttpClient + Send + Sync>>,
+) -> Result<BlockingAdapter, DomeneshopError> {
+    let client = client.ok_or_else(|| {
+        DomeneshopError::configuration(
+            "An underlying client is required without the reqwest-feature",
+        )
+    })?;
+    Ok(BlockingAdapter(client))
+}
+
+#[cfg(feature = "reqwest")]
+fn create_client(
+    client: Option<Box<dyn BlockingHttpClient + Send + Sync>>,
+) -> Result<BlockingAdapter, DomeneshopError> {
+    use crate::http_client;
+
+    if let Some(client) = client {
+        Ok(BlockingAdapter(client))
+    } else {
+        let client = reqwest::blocking::Client::builder()
+            .build()
+            .map_err(|err| {
+                http_client::reqwest::map_reqwest_error("Failed to build reqwest client", err)
+            })?;
+
+        Ok(BlockingAdapter(Box::new(client)))
+    }
+}
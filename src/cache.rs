@@ -0,0 +1,299 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::BuildHasher,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http_types::Method;
+use url::Url;
+
+use crate::{client::API_VERSION, credentials::Credentials, endpoints::domains::DomainId};
+
+/// The read endpoints that can be cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheEndpoint {
+    /// `list_domains` and `list_domains_with_filter`
+    Domains,
+    /// `get_domain`
+    Domain,
+    /// `list_dns_records` and `list_dns_records_with_filter`
+    DnsRecords,
+    /// `get_dns_record`
+    DnsRecord,
+    /// `list_forwards`
+    Forwards,
+    /// `list_invoices` and `list_invoices_with_status`
+    Invoices,
+}
+
+/// An in-memory cache of successful responses from read endpoints.
+///
+/// Only endpoints given a TTL through [`with_ttl`](ResponseCache::with_ttl) are cached.
+/// Responses are cached per url, so lists with different filters are cached separately.
+///
+/// Responses are also cached per credentials, so clients with different credentials sharing a cache
+/// never see each other's responses. Only a hash of the credentials is kept, and the hash is not reused between caches.
+///
+/// Successful mutations through the client invalidate the cached entries of the affected domain.
+/// `update_dyndns` invalidates the DNS records of all domains, since the domain is only known by its hostname.
+/// Use [`invalidate_domain`](ResponseCache::invalidate_domain) after changes made outside of the client.
+///
+/// The cache is cheap to clone, and all clones share the same entries.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use domeneshop_client::cache::{CacheEndpoint, ResponseCache};
+///
+/// let cache = ResponseCache::new()
+///     .with_ttl(CacheEndpoint::Domains, Duration::from_secs(300))
+///     .with_ttl(CacheEndpoint::DnsRecords, Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ResponseCache {
+    ttls: HashMap<CacheEndpoint, Duration>,
+    accounts: RandomState,
+    entries: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+}
+
+/// A hash of the credentials and the url of the request
+type CacheKey = (u64, String);
+
+#[derive(Debug)]
+struct CacheEntry {
+    endpoint: CacheEndpoint,
+    domain_id: Option<DomainId>,
+    body: Vec<u8>,
+    expires: Instant,
+}
+
+impl ResponseCache {
+    /// Creates a cache without any endpoints enabled
+    pub fn new() -> Self {
+        ResponseCache::default()
+    }
+
+    /// Caches responses of the endpoint for `ttl`
+    pub fn with_ttl(mut self, endpoint: CacheEndpoint, ttl: Duration) -> Self {
+        self.ttls.insert(endpoint, ttl);
+        self
+    }
+
+    /// Removes all cached entries of a domain: the domain itself, its DNS records and its forwards
+    pub fn invalidate_domain(&self, domain_id: DomainId) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|_, entry| entry.domain_id != Some(domain_id));
+    }
+
+    /// Removes all cached entries
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    pub(crate) fn get(&self, credentials: &Credentials, url: &Url) -> Option<Vec<u8>> {
+        let mut entries = self.entries.lock().unwrap();
+        let key = self.key(credentials, url);
+        match entries.get(&key) {
+            Some(entry) if entry.expires > Instant::now() => Some(entry.body.clone()),
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, credentials: &Credentials, url: &Url, body: &[u8]) {
+        let Some((endpoint, domain_id)) = classify(url) else {
+            return;
+        };
+        let Some(ttl) = self.ttls.get(&endpoint) else {
+            return;
+        };
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.expires > now);
+        entries.insert(
+            self.key(credentials, url),
+            CacheEntry {
+                endpoint,
+                domain_id,
+                body: body.to_vec(),
+                expires: now + *ttl,
+            },
+        );
+    }
+
+    fn key(&self, credentials: &Credentials, url: &Url) -> CacheKey {
+        let account = self
+            .accounts
+            .hash_one((credentials.token(), credentials.secret()));
+        (account, url.to_string())
+    }
+
+    /// Invalidates the entries affected by a successful request
+    pub(crate) fn observe(&self, method: Method, url: &Url) {
+        let segments = api_segments(url);
+        match segments.as_slice() {
+            ["dyndns", "update"] => self.entries.lock().unwrap().retain(|_, entry| {
+                !matches!(
+                    entry.endpoint,
+                    CacheEndpoint::DnsRecords | CacheEndpoint::DnsRecord
+                )
+            }),
+            _ if method == Method::Get => {}
+            ["domains", id, ..] => {
+                if let Ok(domain_id) = id.parse() {
+                    self.invalidate_domain(domain_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Finds the endpoint and domain of a read request
+fn classify(url: &Url) -> Option<(CacheEndpoint, Option<DomainId>)> {
+    let segments = api_segments(url);
+    let domain_id = |id: &str| id.parse::<DomainId>().ok();
+    match segments.as_slice() {
+        ["domains"] => Some((CacheEndpoint::Domains, None)),
+        ["domains", id] => Some((CacheEndpoint::Domain, Some(domain_id(id)?))),
+        ["domains", id, "dns"] => Some((CacheEndpoint::DnsRecords, Some(domain_id(id)?))),
+        ["domains", id, "dns", _] => Some((CacheEndpoint::DnsRecord, Some(domain_id(id)?))),
+        ["domains", id, "forwards"] => Some((CacheEndpoint::Forwards, Some(domain_id(id)?))),
+        ["invoices"] => Some((CacheEndpoint::Invoices, None)),
+        _ => None,
+    }
+}
+
+/// The path segments after the API version, e.g. `["domains", "1", "dns"]`
+pub(crate) fn api_segments(url: &Url) -> Vec<&str> {
+    let segments: Vec<_> = url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    match segments.iter().position(|segment| *segment == API_VERSION) {
+        Some(index) => segments[index + 1..].to_vec(),
+        None => segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use http_types::Method;
+    use url::Url;
+
+    use crate::credentials::Credentials;
+
+    use super::{api_segments, classify, CacheEndpoint, ResponseCache};
+
+    fn credentials() -> Credentials {
+        Credentials::new("token", "secret")
+    }
+
+    fn url(path: &str) -> Url {
+        Url::parse(&format!("https://api.domeneshop.no/v0{}", path)).unwrap()
+    }
+
+    fn cache() -> ResponseCache {
+        ResponseCache::new()
+            .with_ttl(CacheEndpoint::Domain, Duration::from_secs(60))
+            .with_ttl(CacheEndpoint::DnsRecords, Duration::from_secs(60))
+            .with_ttl(CacheEndpoint::Forwards, Duration::from_secs(60))
+    }
+
+    #[test]
+    fn api_segments_skip_base_path() {
+        let url = Url::parse("https://proxy.local/domeneshop/v0/domains/1/dns").unwrap();
+
+        assert_eq!(api_segments(&url), vec!["domains", "1", "dns"]);
+    }
+
+    #[test]
+    fn classify_finds_endpoint_and_domain() {
+        assert_eq!(
+            classify(&url("/domains?domain=.no")),
+            Some((CacheEndpoint::Domains, None))
+        );
+        assert_eq!(
+            classify(&url("/domains/1/dns/2")),
+            Some((CacheEndpoint::DnsRecord, Some(1)))
+        );
+        assert_eq!(
+            classify(&url("/domains/1/forwards")),
+            Some((CacheEndpoint::Forwards, Some(1)))
+        );
+        assert_eq!(classify(&url("/domains/1/forwards/www")), None);
+        assert_eq!(classify(&url("/domains/x/dns")), None);
+    }
+
+    #[test]
+    fn only_endpoints_with_ttl_are_cached() {
+        let cache = cache();
+
+        cache.insert(&credentials(), &url("/domains/1"), b"domain");
+        cache.insert(&credentials(), &url("/domains"), b"domains");
+
+        assert_eq!(
+            cache.get(&credentials(), &url("/domains/1")),
+            Some(b"domain".to_vec())
+        );
+        assert_eq!(cache.get(&credentials(), &url("/domains")), None);
+    }
+
+    #[test]
+    fn entries_are_kept_per_credentials() {
+        let cache = cache();
+
+        cache.insert(&credentials(), &url("/domains/1"), b"domain");
+
+        let other = Credentials::new("token", "other");
+        assert_eq!(cache.get(&other, &url("/domains/1")), None);
+        assert!(cache.get(&credentials(), &url("/domains/1")).is_some());
+    }
+
+    #[test]
+    fn expired_entries_are_not_returned() {
+        let cache = ResponseCache::new().with_ttl(CacheEndpoint::Domains, Duration::ZERO);
+
+        cache.insert(&credentials(), &url("/domains"), b"domains");
+
+        assert_eq!(cache.get(&credentials(), &url("/domains")), None);
+    }
+
+    #[test]
+    fn mutations_invalidate_the_domain() {
+        let cache = cache();
+        cache.insert(&credentials(), &url("/domains/1"), b"domain");
+        cache.insert(&credentials(), &url("/domains/1/dns"), b"dns");
+        cache.insert(&credentials(), &url("/domains/2/dns"), b"other");
+
+        cache.observe(Method::Get, &url("/domains/1/forwards"));
+        assert!(cache.get(&credentials(), &url("/domains/1/dns")).is_some());
+
+        cache.observe(Method::Post, &url("/domains/1/forwards"));
+        assert_eq!(cache.get(&credentials(), &url("/domains/1")), None);
+        assert_eq!(cache.get(&credentials(), &url("/domains/1/dns")), None);
+        assert!(cache.get(&credentials(), &url("/domains/2/dns")).is_some());
+    }
+
+    #[test]
+    fn dyndns_invalidates_all_dns_records() {
+        let cache = cache();
+        cache.insert(&credentials(), &url("/domains/1"), b"domain");
+        cache.insert(&credentials(), &url("/domains/1/dns"), b"dns");
+        cache.insert(&credentials(), &url("/domains/2/dns"), b"other");
+
+        cache.observe(Method::Get, &url("/dyndns/update?hostname=www.example.com"));
+
+        assert!(cache.get(&credentials(), &url("/domains/1")).is_some());
+        assert_eq!(cache.get(&credentials(), &url("/domains/1/dns")), None);
+        assert_eq!(cache.get(&credentials(), &url("/domains/2/dns")), None);
+    }
+}
@@ -1,50 +1,111 @@
-use std::borrow::Borrow;
+use std::{borrow::Borrow, sync::Arc};
 
 use base64::{engine::general_purpose, Engine};
 use http_types::{Method, Request, Response};
 use serde::{de::DeserializeOwned, Serialize};
 use url::Url;
+use zeroize::Zeroizing;
 
 use crate::{
-    errors::{to_domain_error, to_domain_error_with_context, DomeneshopApiError, DomeneshopError},
+    audit::AuditLog,
+    cache::ResponseCache,
+    credentials::Credentials,
+    dry_run::DryRun,
+    endpoints::domains::DomainId,
+    errors::{to_deserialization_error, to_url_error, DomeneshopApiError, DomeneshopError},
     http::HttpClient,
+    layer::{Layer, Next},
+    rate_limit::RateLimiter,
+    retry::{execute_with_retry, RetryPolicy},
 };
 
+mod builder;
+pub(crate) mod loader;
+
+pub use builder::{DomeneshopClientBuilder, TlsBackend};
+pub use loader::{BASE_URL_ENV, SECRET_ENV, TOKEN_ENV};
+
 /// Configuration of the created client
 pub struct DomeneshopClientConfiguration {
     /// Overrides default user agent-header if set
     pub user_agent: Option<String>,
     /// Overrides default base url if set
     pub base_url: Option<String>,
-    /// Sets an optional underlying client.
-    /// Without `reqwest`-feature enabled, this is NOT optional
-    #[cfg(feature = "reqwest")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
-    pub underlying_client: Option<Box<dyn HttpClient + 'static>>,
-    /// Sets a required underlying client (only with `reqwest` feature disabled)
-    #[cfg(not(feature = "reqwest"))]
-    #[cfg_attr(docsrs, not(doc(cfg(feature = "reqwest"))))]
-    pub underlying_client: Box<dyn HttpClient>,
+    /// Decides if and how failed requests are retried.
+    /// See [`RetryPolicy`] for the defaults.
+    pub retry_policy: RetryPolicy,
+    /// Optional client-side rate limiter. Disabled if not set.
+    pub rate_limiter: Option<RateLimiter>,
+    /// Optional cache of responses from read endpoints. Disabled if not set.
+    /// See [`ResponseCache`].
+    pub cache: Option<ResponseCache>,
+    /// Records mutations instead of sending them if set. See [`DryRun`].
+    pub dry_run: Option<DryRun>,
+    /// Optional audit log receiving an event for every mutating call. Disabled if not set.
+    /// See [`AuditLog`].
+    pub audit: Option<AuditLog>,
+    /// Layers wrapping the underlying client, invoked in the given order for every request.
+    /// See [`Layer`].
+    pub layers: Vec<Box<dyn Layer>>,
 }
 
-#[cfg(feature = "reqwest")]
-impl DomeneshopClientConfiguration {
+impl Default for DomeneshopClientConfiguration {
     /// Creates a default configuration for the domeneshop client
-    pub fn default() -> DomeneshopClientConfiguration {
+    fn default() -> DomeneshopClientConfiguration {
         DomeneshopClientConfiguration {
             user_agent: None,
             base_url: None,
-            underlying_client: None,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            cache: None,
+            dry_run: None,
+            audit: None,
+            layers: Vec::new(),
         }
     }
 }
 
 /// The client used to interact with the domeneshop API.
-pub struct DomeneshopClient {
-    client: Box<dyn HttpClient>,
+///
+/// The client is cheap to clone, and all clones share the same underlying client, rate limiter and cache.
+/// It is `Send` and `Sync`, and so are the futures returned by its operations,
+/// so it can be shared between tasks, e.g. as state in a web server.
+///
+/// The client is generic over the [`HttpClient`] used to send requests.
+/// It defaults to [`DefaultBackend`], and a custom backend is given through [`with_http_client`](DomeneshopClient::with_http_client).
+pub struct DomeneshopClient<H: HttpClient = DefaultBackend> {
+    inner: Arc<ClientInner<H>>,
+}
+
+/// The [`HttpClient`] used by [`DomeneshopClient::new`].
+/// This is `reqwest::Client` with the `reqwest`-feature enabled, and [`BoxedHttpClient`](crate::http::BoxedHttpClient) otherwise.
+#[cfg(feature = "reqwest")]
+pub type DefaultBackend = reqwest::Client;
+
+/// The [`HttpClient`] used by [`DomeneshopClient::new`].
+/// This is `reqwest::Client` with the `reqwest`-feature enabled, and [`BoxedHttpClient`](crate::http::BoxedHttpClient) otherwise.
+#[cfg(not(feature = "reqwest"))]
+pub type DefaultBackend = crate::http::BoxedHttpClient;
+
+impl<H: HttpClient> Clone for DomeneshopClient<H> {
+    fn clone(&self) -> Self {
+        DomeneshopClient {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+struct ClientInner<H> {
+    client: H,
     base_url: String,
-    auth_header: String,
+    credentials: Credentials,
     user_agent: String,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    cache: Option<ResponseCache>,
+    dry_run: Option<DryRun>,
+    audit: Option<AuditLog>,
+    layers: Vec<Box<dyn Layer>>,
 }
 
 const DEFAULT_USER_AGENT: &str = concat!(
@@ -63,33 +124,189 @@ impl DomeneshopClient {
         token: String,
         secret: String,
         configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        DomeneshopClient::with_credentials(Credentials::new(token, secret), configuration)
+    }
+
+    /// Creates a new domeneshop client using the given credentials
+    pub fn with_credentials(
+        credentials: Credentials,
+        configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        DomeneshopClient::with_http_client(credentials, create_client()?, configuration)
+    }
+}
+
+impl<H: HttpClient> DomeneshopClient<H> {
+    /// Creates a new domeneshop client sending requests through `http_client`
+    pub fn with_http_client(
+        credentials: Credentials,
+        http_client: H,
+        configuration: DomeneshopClientConfiguration,
     ) -> Result<Self, DomeneshopError> {
         let user_agent = configuration
             .user_agent
-            .clone()
             .unwrap_or(DEFAULT_USER_AGENT.to_string());
 
         let base_url = configuration
             .base_url
-            .clone()
             .unwrap_or(DEFAULT_BASE_URL.to_string());
 
-        let client = create_client(configuration)?;
-        let header = create_basic_auth_header(token, secret);
         Ok(DomeneshopClient {
-            client,
-            base_url: format!("{}/{}", strip_trailing_slash(base_url), API_VERSION),
-            auth_header: header,
-            user_agent,
+            inner: Arc::new(ClientInner {
+                client: http_client,
+                base_url: format!("{}/{}", strip_trailing_slash(base_url), API_VERSION),
+                credentials,
+                user_agent,
+                retry_policy: configuration.retry_policy,
+                rate_limiter: configuration.rate_limiter,
+                cache: configuration.cache,
+                dry_run: configuration.dry_run,
+                audit: configuration.audit,
+                layers: configuration.layers,
+            }),
         })
     }
 
+    /// Removes all cached responses of a domain. Does nothing if the client has no [`ResponseCache`].
+    pub fn invalidate_domain(&self, domain_id: DomainId) {
+        if let Some(cache) = &self.inner.cache {
+            cache.invalidate_domain(domain_id);
+        }
+    }
+
+    /// Sends a request to an endpoint without a dedicated operation, deserializing the JSON-response.
+    ///
+    /// `path` is relative to the API version, e.g. `/domains/1/dns`.
+    /// The request is authenticated and retried like all other operations,
+    /// and a response with an unsuccessful status is returned as an error.
+    /// An empty response body is deserialized as `null`, so `()` can be used for endpoints without content.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use domeneshop_client::client::{DomeneshopClient, DomeneshopClientConfiguration};
+    /// use domeneshop_client::endpoints::domains::Domain;
+    /// use http_types::Method;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # tokio_test::block_on(async {
+    /// let client = DomeneshopClient::new(
+    ///     String::from("token"),
+    ///     String::from("secret"),
+    ///     DomeneshopClientConfiguration::default(),
+    /// )?;
+    /// let domains: Vec<Domain> = client
+    ///     .request::<(), _>(Method::Get, "/domains", &[("domain", ".no")], None)
+    ///     .await?;
+    /// #     Ok(())
+    /// # })
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, query, body),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
+    pub async fn request<B, T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<T, DomeneshopError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        if method == Method::Get && body.is_none() {
+            return self
+                .get_response(self.create_request_url(path, query)?)
+                .await;
+        }
+        let mut response = self.send_request(method, path, query, body).await?;
+        let body = response
+            .body_bytes()
+            .await
+            .map_err(to_deserialization_error)?;
+        let body = if body.is_empty() {
+            b"null".to_vec()
+        } else {
+            body
+        };
+        serde_json::from_slice(&body).map_err(|err| to_deserialization_error(err.into()))
+    }
+
+    /// Sends a request to an endpoint without a dedicated operation, returning the response as is.
+    ///
+    /// Works like [`request`](DomeneshopClient::request), but leaves reading the body to the caller.
+    /// A response with an unsuccessful status is still returned as an error.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, query, body),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
+    pub async fn request_raw<B>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<Response, DomeneshopError>
+    where
+        B: Serialize + ?Sized,
+    {
+        self.send_request(method, path, query, body).await
+    }
+
+    async fn send_request<B>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<Response, DomeneshopError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let mut request = Request::new(method, self.create_request_url(path, query)?);
+        if let Some(body) = body {
+            let json = serde_json::to_vec(body).map_err(|err| {
+                DomeneshopError::new("Failed to serialize request body").with_source(err)
+            })?;
+            request.set_body(json);
+            request.set_content_type(http_types::mime::JSON);
+        }
+        self.send(request).await
+    }
+
+    fn create_request_url(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Url, DomeneshopError> {
+        if query.is_empty() {
+            self.create_url(path)
+        } else {
+            self.create_url_with_parameters(path, query)
+        }
+    }
+
     pub(crate) fn create_url(
         &self,
         relative_url: impl Into<String>,
     ) -> Result<Url, DomeneshopError> {
-        let url = format!("{}/{}", self.base_url, strip_leading_slash(relative_url));
-        Url::parse(url.as_str()).map_err(to_domain_error)
+        let url = format!(
+            "{}/{}",
+            self.inner.base_url,
+            strip_leading_slash(relative_url)
+        );
+        Url::parse(url.as_str()).map_err(to_url_error)
     }
 
     pub(crate) fn create_url_with_parameters<S, I, K, V>(
@@ -104,24 +321,96 @@ impl DomeneshopClient {
         K: AsRef<str>,
         V: AsRef<str>,
     {
-        let url = format!("{}/{}", self.base_url, strip_leading_slash(relative_url));
-        Url::parse_with_params(url.as_str(), query_parameters).map_err(to_domain_error)
+        let url = format!(
+            "{}/{}",
+            self.inner.base_url,
+            strip_leading_slash(relative_url)
+        );
+        Url::parse_with_params(url.as_str(), query_parameters).map_err(to_url_error)
     }
 
     pub(crate) async fn send_no_validation(
         &self,
         mut req: Request,
     ) -> Result<Response, DomeneshopError> {
-        req.insert_header("Authorization", &self.auth_header);
-        req.insert_header("User-Agent", &self.user_agent);
-        self.client.execute_request(req).await
+        req.insert_header(
+            "Authorization",
+            create_basic_auth_header(&self.inner.credentials).as_str(),
+        );
+        req.insert_header("User-Agent", &self.inner.user_agent);
+
+        #[cfg(feature = "tracing")]
+        let started = trace_request(&req);
+
+        let result =
+            execute_with_retry(&self.inner.retry_policy, req, |req| self.execute(req)).await;
+
+        #[cfg(feature = "tracing")]
+        trace_response(&result, started);
+
+        result
+    }
+
+    async fn execute(&self, req: Request) -> Result<Response, DomeneshopError> {
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let response = if self.inner.layers.is_empty() {
+            self.inner.client.execute_request(req).await?
+        } else {
+            Next::new(&self.inner.layers, &self.inner.client)
+                .run(req)
+                .await?
+        };
+        if let Some(rate_limiter) = &self.inner.rate_limiter {
+            rate_limiter.observe(&response);
+        }
+        Ok(response)
     }
 
-    pub(crate) async fn send(&self, req: Request) -> Result<Response, DomeneshopError> {
+    pub(crate) async fn send(&self, mut req: Request) -> Result<Response, DomeneshopError> {
+        let Some(audit) = &self.inner.audit else {
+            return self.send_unaudited(req).await;
+        };
+        let Some((mut event, before_url)) = audit.begin(&mut req).await? else {
+            return self.send_unaudited(req).await;
+        };
+        if let Some(url) = before_url {
+            event.before = self.fetch_before(url).await;
+        }
+        let mut result = self.send_unaudited(req).await;
+        audit
+            .finish(event, &mut result, self.inner.dry_run.is_some())
+            .await;
+        result
+    }
+
+    async fn fetch_before(&self, url: Url) -> Option<serde_json::Value> {
+        let mut response = self
+            .send_no_validation(Request::new(Method::Get, url))
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.body_json().await.ok()
+    }
+
+    async fn send_unaudited(&self, mut req: Request) -> Result<Response, DomeneshopError> {
+        if let Some(dry_run) = &self.inner.dry_run {
+            if let Some(response) = dry_run.intercept(&mut req).await? {
+                return Ok(response);
+            }
+        }
+        let method = req.method();
+        let url = req.url().clone();
         let response = self.send_no_validation(req).await?;
         if !response.status().is_success() {
             Err(handle_response_error(response).await)
         } else {
+            if let Some(cache) = &self.inner.cache {
+                cache.observe(method, &url);
+            }
             Ok(response)
         }
     }
@@ -133,41 +422,64 @@ impl DomeneshopClient {
     where
         T: DeserializeOwned,
     {
-        response.body_json().await.map_err(to_domain_error)
+        response.body_json().await.map_err(to_deserialization_error)
     }
 
     pub(crate) async fn get_response<T>(&self, url: Url) -> Result<T, DomeneshopError>
     where
         T: DeserializeOwned,
     {
-        let request = Request::new(Method::Get, url);
-        let response = self.send(request).await?;
+        let Some(cache) = &self.inner.cache else {
+            let request = Request::new(Method::Get, url);
+            let response = self.send(request).await?;
+            return self.deserialize_response(response).await;
+        };
 
-        self.deserialize_response(response).await
+        if let Some(body) = cache.get(&self.inner.credentials, &url) {
+            return serde_json::from_slice(&body)
+                .map_err(|err| to_deserialization_error(err.into()));
+        }
+        let request = Request::new(Method::Get, url.clone());
+        let mut response = self.send(request).await?;
+        let body = response
+            .body_bytes()
+            .await
+            .map_err(to_deserialization_error)?;
+        let model =
+            serde_json::from_slice(&body).map_err(|err| to_deserialization_error(err.into()))?;
+        cache.insert(&self.inner.credentials, &url, &body);
+        Ok(model)
     }
 }
 
 #[cfg(not(feature = "reqwest"))]
-fn create_client(
-    config: DomeneshopClientConfiguration,
-) -> Result<Box<dyn HttpClient>, DomeneshopError> {
-    Ok(config.underlying_client)
+fn create_client() -> Result<DefaultBackend, DomeneshopError> {
+    Err(DomeneshopError::configuration(
+        "An underlying client is required without the reqwest-feature. Use DomeneshopClient::with_http_client",
+    ))
 }
 
 #[cfg(feature = "reqwest")]
-fn create_client(
-    config: DomeneshopClientConfiguration,
-) -> Result<Box<dyn HttpClient>, DomeneshopError> {
-    use crate::http_client;
+fn create_client() -> Result<DefaultBackend, DomeneshopError> {
+    reqwest::Client::builder().build().map_err(|err| {
+        crate::http_client::reqwest::map_reqwest_error("Failed to build reqwest client", err)
+    })
+}
 
-    if let Some(client) = config.underlying_client {
-        Ok(client)
-    } else {
-        let client = reqwest::Client::builder().build().map_err(|err| {
-            http_client::reqwest::map_reqwest_error("Failed to build reqwest client", err)
-        })?;
+#[cfg(feature = "tracing")]
+fn trace_request(request: &Request) -> std::time::Instant {
+    let span = tracing::Span::current();
+    span.record("http.method", request.method().as_ref());
+    span.record("url.path", request.url().path());
+    std::time::Instant::now()
+}
 
-        Ok(Box::new(client))
+#[cfg(feature = "tracing")]
+fn trace_response(result: &Result<Response, DomeneshopError>, started: std::time::Instant) {
+    let span = tracing::Span::current();
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    if let Ok(response) = result {
+        span.record("http.status_code", u16::from(response.status()));
     }
 }
 
@@ -182,17 +494,16 @@ fn strip_trailing_slash(s: impl Into<String>) -> String {
 
 fn strip_leading_slash(s: impl Into<String>) -> String {
     let s: String = s.into();
-    if s.starts_with('/') {
-        s[1..].to_string()
-    } else {
-        s
+    match s.strip_prefix('/') {
+        Some(stripped) => stripped.to_string(),
+        None => s,
     }
 }
 
-fn create_basic_auth_header(token: String, secret: String) -> String {
-    let val = format!("{}:{}", token, secret);
-    let encoded = general_purpose::STANDARD_NO_PAD.encode(val);
-    format!("Basic {}", encoded)
+fn create_basic_auth_header(credentials: &Credentials) -> Zeroizing<String> {
+    let val = Zeroizing::new(format!("{}:{}", credentials.token(), credentials.secret()));
+    let encoded = Zeroizing::new(general_purpose::STANDARD_NO_PAD.encode(val.as_bytes()));
+    Zeroizing::new(format!("Basic {}", encoded.as_str()))
 }
 
 pub(crate) fn set_body<T>(request: &mut Request, model: T)
@@ -204,9 +515,10 @@ where
 }
 
 pub(crate) async fn handle_response_error(mut response: Response) -> DomeneshopError {
+    let status = response.status();
     match response.body_json::<DomeneshopApiError>().await {
-        Ok(error) => error.into(),
-        Err(err) => to_domain_error_with_context("Failed to deserialize error response", err),
+        Ok(error) => error.into_error(status),
+        Err(err) => DomeneshopError::unexpected_status(status).with_source(err.into_inner()),
     }
 }
 
@@ -221,7 +533,7 @@ mod tests {
         http_client::mock::MockClient,
     };
 
-    use super::{create_basic_auth_header, DomeneshopClient, DomeneshopError};
+    use super::{create_basic_auth_header, Credentials, DomeneshopClient, DomeneshopError};
 
     async fn return_ok(_: Request) -> Result<Response, DomeneshopError> {
         Ok(Response::new(StatusCode::Ok))
@@ -229,11 +541,10 @@ mod tests {
 
     #[test]
     fn create_basic_auth_header_creates_valid_header() {
-        let token = String::from("token");
-        let secret = String::from("secret");
-        let result = create_basic_auth_header(token, secret);
+        let credentials = Credentials::new("token", "secret");
+        let result = create_basic_auth_header(&credentials);
 
-        assert_eq!(result, "Basic dG9rZW46c2VjcmV0");
+        assert_eq!(result.as_str(), "Basic dG9rZW46c2VjcmV0");
     }
 
     #[test]
@@ -324,13 +635,12 @@ mod tests {
         let mock = MockClient {
             req_received: received,
         };
-        let client = DomeneshopClient::new(
-            String::from("token"),
-            String::from("secret"),
+        let client = DomeneshopClient::with_http_client(
+            Credentials::new("token", "secret"),
+            mock,
             super::DomeneshopClientConfiguration {
                 user_agent: Some(USER_AGENT.to_string()),
-                base_url: None,
-                underlying_client: Some(Box::new(mock)),
+                ..Default::default()
             },
         )
         .unwrap();
@@ -341,18 +651,14 @@ mod tests {
             .unwrap();
     }
 
-    fn create_client<'a, F>(client: MockClient<F>) -> DomeneshopClient
+    fn create_client<F>(client: MockClient<F>) -> DomeneshopClient<MockClient<F>>
     where
         F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
     {
-        DomeneshopClient::new(
-            String::from("token"),
-            String::from("secret"),
-            super::DomeneshopClientConfiguration {
-                user_agent: None,
-                base_url: None,
-                underlying_client: Some(Box::new(client)),
-            },
+        DomeneshopClient::with_http_client(
+            Credentials::new("token", "secret"),
+            client,
+            super::DomeneshopClientConfiguration::default(),
         )
         .unwrap()
     }
@@ -0,0 +1,525 @@
+use std::time::Duration;
+
+use url::Url;
+
+use crate::{
+    audit::AuditLog, cache::ResponseCache, credentials::Credentials, dry_run::DryRun,
+    errors::DomeneshopError, http::HttpClient, layer::Layer, rate_limit::RateLimiter,
+    retry::RetryPolicy,
+};
+
+use super::{DefaultBackend, DomeneshopClient, DomeneshopClientConfiguration};
+
+/// The TLS implementation used by the underlying `reqwest`-client
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TlsBackend {
+    /// Uses [rustls](https://crates.io/crates/rustls). Requires the `rustls-tls`-feature.
+    Rustls,
+    /// Uses the TLS implementation of the platform. Requires enabling the `native-tls`-feature of this crate.
+    NativeTls,
+}
+
+/// Builder for [`DomeneshopClient`], created by [`DomeneshopClient::builder`].
+///
+/// All settings are validated when calling [`build`](DomeneshopClientBuilder::build).
+///
+/// When no client is supplied through [`http_client`](DomeneshopClientBuilder::http_client),
+/// the builder configures the underlying `reqwest`-client with the given timeouts, proxy and TLS settings.
+/// These settings cannot be combined with a custom client.
+/// The builder is generic over the supplied client, so the built client is a `DomeneshopClient<H>`.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use domeneshop_client::client::DomeneshopClient;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = DomeneshopClient::builder()
+///     .credentials("token", "secret")
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(30))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DomeneshopClientBuilder<H = NoHttpClient> {
+    credentials: Option<Credentials>,
+    base_url: Option<Url>,
+    user_agent: Option<String>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Url>,
+    root_certificates: Vec<Vec<u8>>,
+    tls_backend: Option<TlsBackend>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    cache: Option<ResponseCache>,
+    dry_run: Option<DryRun>,
+    audit: Option<AuditLog>,
+    layers: Vec<Box<dyn Layer>>,
+    http_client: H,
+}
+
+/// Marks a [`DomeneshopClientBuilder`] without a custom [`HttpClient`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoHttpClient;
+
+impl Default for DomeneshopClientBuilder {
+    fn default() -> Self {
+        DomeneshopClientBuilder {
+            credentials: None,
+            base_url: None,
+            user_agent: None,
+            connect_timeout: None,
+            timeout: None,
+            proxy: None,
+            root_certificates: Vec::new(),
+            tls_backend: None,
+            retry_policy: None,
+            rate_limiter: None,
+            cache: None,
+            dry_run: None,
+            audit: None,
+            layers: Vec::new(),
+            http_client: NoHttpClient,
+        }
+    }
+}
+
+impl DomeneshopClient {
+    /// Creates a builder for configuring a new client
+    pub fn builder() -> DomeneshopClientBuilder {
+        DomeneshopClientBuilder::default()
+    }
+}
+
+impl<H> DomeneshopClientBuilder<H> {
+    /// Sets the API credentials. Either this or [`with_credentials`](DomeneshopClientBuilder::with_credentials) is required.
+    pub fn credentials(self, token: impl Into<String>, secret: impl Into<String>) -> Self {
+        self.with_credentials(Credentials::new(token, secret))
+    }
+
+    /// Sets the API credentials
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Overrides the base url of the API. Must be an absolute `http` or `https` url.
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Overrides the default user agent-header
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for a complete request, from connecting until the response body has been received
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through the given proxy. Supports `http` and `https` proxies.
+    pub fn proxy(mut self, proxy: Url) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Adds a trusted root certificate in PEM-format
+    pub fn add_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Chooses the TLS implementation
+    pub fn tls_backend(mut self, tls_backend: TlsBackend) -> Self {
+        self.tls_backend = Some(tls_backend);
+        self
+    }
+
+    /// Decides if and how failed requests are retried
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Enables client-side rate limiting
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Caches responses from read endpoints
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Records mutations instead of sending them
+    pub fn dry_run(mut self, dry_run: DryRun) -> Self {
+        self.dry_run = Some(dry_run);
+        self
+    }
+
+    /// Sends an event to the audit log for every mutating call
+    pub fn audit(mut self, audit: AuditLog) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Adds a layer wrapping the underlying client. Layers are invoked in the order they are added.
+    pub fn layer(mut self, layer: impl Layer + 'static) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Uses a custom implementation of [`HttpClient`] instead of the default `reqwest`-implementation.
+    /// Required without the `reqwest`-feature.
+    pub fn http_client<C: HttpClient>(self, http_client: C) -> DomeneshopClientBuilder<C> {
+        DomeneshopClientBuilder {
+            credentials: self.credentials,
+            base_url: self.base_url,
+            user_agent: self.user_agent,
+            connect_timeout: self.connect_timeout,
+            timeout: self.timeout,
+            proxy: self.proxy,
+            root_certificates: self.root_certificates,
+            tls_backend: self.tls_backend,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            cache: self.cache,
+            dry_run: self.dry_run,
+            audit: self.audit,
+            layers: self.layers,
+            http_client,
+        }
+    }
+
+    fn validate(&mut self) -> Result<Credentials, DomeneshopError> {
+        let credentials = match self.credentials.take() {
+            Some(credentials) if !credentials.is_empty() => credentials,
+            _ => return Err(DomeneshopError::configuration("Credentials are required")),
+        };
+
+        if let Some(base_url) = &self.base_url {
+            validate_base_url(base_url)?;
+        }
+        if let Some(user_agent) = &self.user_agent {
+            if !user_agent
+                .bytes()
+                .all(|byte| byte == b'\t' || (b' '..=b'~').contains(&byte))
+            {
+                return Err(DomeneshopError::configuration(format!(
+                    "Invalid user agent: {:?}",
+                    user_agent
+                )));
+            }
+        }
+        for timeout in [self.connect_timeout, self.timeout].into_iter().flatten() {
+            if timeout.is_zero() {
+                return Err(DomeneshopError::configuration(
+                    "Timeouts must be greater than zero",
+                ));
+            }
+        }
+        if let Some(proxy) = &self.proxy {
+            if !matches!(proxy.scheme(), "http" | "https") {
+                return Err(DomeneshopError::configuration(format!(
+                    "Unsupported proxy scheme: {}",
+                    proxy.scheme()
+                )));
+            }
+        }
+        Ok(credentials)
+    }
+
+    fn configures_transport(&self) -> bool {
+        self.connect_timeout.is_some()
+            || self.timeout.is_some()
+            || self.proxy.is_some()
+            || !self.root_certificates.is_empty()
+            || self.tls_backend.is_some()
+    }
+
+    fn into_parts(self) -> (DomeneshopClientConfiguration, H) {
+        let configuration = DomeneshopClientConfiguration {
+            user_agent: self.user_agent,
+            base_url: self.base_url.map(|url| url.to_string()),
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            rate_limiter: self.rate_limiter,
+            cache: self.cache,
+            dry_run: self.dry_run,
+            audit: self.audit,
+            layers: self.layers,
+        };
+        (configuration, self.http_client)
+    }
+}
+
+impl DomeneshopClientBuilder {
+    /// Validates the settings and creates the client using the default `reqwest`-implementation
+    pub fn build(mut self) -> Result<DomeneshopClient, DomeneshopError> {
+        let credentials = self.validate()?;
+        let http_client = build_http_client(&self)?;
+        let (configuration, _) = self.into_parts();
+        DomeneshopClient::with_http_client(credentials, http_client, configuration)
+    }
+}
+
+impl<H: HttpClient> DomeneshopClientBuilder<H> {
+    /// Validates the settings and creates the client using the supplied [`HttpClient`]
+    pub fn build(mut self) -> Result<DomeneshopClient<H>, DomeneshopError> {
+        let credentials = self.validate()?;
+        if self.configures_transport() {
+            return Err(DomeneshopError::configuration(
+                "Timeouts, proxy and TLS settings cannot be combined with a custom http client",
+            ));
+        }
+        let (configuration, http_client) = self.into_parts();
+        DomeneshopClient::with_http_client(credentials, http_client, configuration)
+    }
+}
+
+fn validate_base_url(base_url: &Url) -> Result<(), DomeneshopError> {
+    if !matches!(base_url.scheme(), "http" | "https") || base_url.cannot_be_a_base() {
+        return Err(DomeneshopError::configuration(format!(
+            "Base url must be an absolute http or https url: {}",
+            base_url
+        )));
+    }
+    if base_url.query().is_some() || base_url.fragment().is_some() {
+        return Err(DomeneshopError::configuration(format!(
+            "Base url cannot contain a query or fragment: {}",
+            base_url
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn build_http_client(
+    _builder: &DomeneshopClientBuilder,
+) -> Result<DefaultBackend, DomeneshopError> {
+    Err(DomeneshopError::configuration(
+        "An http client is required without the reqwest-feature",
+    ))
+}
+
+#[cfg(feature = "reqwest")]
+fn build_http_client(builder: &DomeneshopClientBuilder) -> Result<DefaultBackend, DomeneshopError> {
+    use crate::http_client::reqwest::map_reqwest_error;
+
+    let mut client_builder = reqwest::Client::builder();
+
+    if let Some(tls_backend) = builder.tls_backend {
+        client_builder = use_tls_backend(client_builder, tls_backend)?;
+    }
+
+    if let Some(timeout) = builder.connect_timeout {
+        client_builder = client_builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = builder.timeout {
+        client_builder = client_builder.timeout(timeout);
+    }
+    if let Some(proxy) = &builder.proxy {
+        let proxy = reqwest::Proxy::all(proxy.as_str()).map_err(|err| {
+            DomeneshopError::configuration(format!("Invalid proxy: {}", err)).with_source(err)
+        })?;
+        client_builder = client_builder.proxy(proxy);
+    }
+    for pem in &builder.root_certificates {
+        let certificate = reqwest::Certificate::from_pem(pem).map_err(|err| {
+            DomeneshopError::configuration(format!("Invalid root certificate: {}", err))
+                .with_source(err)
+        })?;
+        client_builder = client_builder.add_root_certificate(certificate);
+    }
+
+    let client = client_builder
+        .build()
+        .map_err(|err| map_reqwest_error("Failed to build reqwest client", err))?;
+    Ok(client)
+}
+
+#[cfg(feature = "reqwest")]
+#[cfg_attr(
+    not(any(feature = "rustls-tls", feature = "native-tls")),
+    allow(unused_variables)
+)]
+fn use_tls_backend(
+    client_builder: reqwest::ClientBuilder,
+    tls_backend: TlsBackend,
+) -> Result<reqwest::ClientBuilder, DomeneshopError> {
+    match tls_backend {
+        #[cfg(feature = "rustls-tls")]
+        TlsBackend::Rustls => Ok(client_builder.use_rustls_tls()),
+        #[cfg(feature = "native-tls")]
+        TlsBackend::NativeTls => Ok(client_builder.use_native_tls()),
+        #[allow(unreachable_patterns)]
+        _ => Err(DomeneshopError::configuration(format!(
+            "TLS backend {:?} is not enabled. Enable the corresponding feature of this crate",
+            tls_backend
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use http_types::{Request, Response, StatusCode};
+    use url::Url;
+
+    use crate::{
+        errors::{DomeneshopError, DomeneshopErrorKind},
+        http::HttpClient,
+        http_client::mock::MockClient,
+    };
+
+    use super::{DomeneshopClient, TlsBackend};
+
+    async fn return_ok(req: Request) -> Result<Response, DomeneshopError> {
+        assert_eq!(
+            req.url().as_str(),
+            "https://proxy.local/domeneshop/v0/domains"
+        );
+        assert_eq!(req.header("User-Agent").unwrap(), "agent");
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    fn assert_configuration_error<H: HttpClient>(
+        result: Result<DomeneshopClient<H>, DomeneshopError>,
+    ) {
+        match result {
+            Err(err) => assert_eq!(err.kind(), &DomeneshopErrorKind::Configuration),
+            Ok(_) => panic!("Expected configuration error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn build_uses_settings() {
+        let client = DomeneshopClient::builder()
+            .credentials("token", "secret")
+            .base_url(Url::parse("https://proxy.local/domeneshop/").unwrap())
+            .user_agent("agent")
+            .http_client(MockClient {
+                req_received: return_ok,
+            })
+            .build()
+            .unwrap();
+
+        client.list_domains().await.unwrap();
+    }
+
+    #[test]
+    fn build_configures_reqwest_client() {
+        let client = DomeneshopClient::builder()
+            .credentials("token", "secret")
+            .connect_timeout(Duration::from_secs(1))
+            .timeout(Duration::from_secs(10))
+            .proxy(Url::parse("http://localhost:3128").unwrap())
+            .build();
+
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn build_requires_credentials() {
+        assert_configuration_error(DomeneshopClient::builder().build());
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("", "secret")
+                .build(),
+        );
+    }
+
+    #[test]
+    fn build_rejects_invalid_base_url() {
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .base_url(Url::parse("ftp://example.com").unwrap())
+                .build(),
+        );
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .base_url(Url::parse("https://example.com?a=b").unwrap())
+                .build(),
+        );
+    }
+
+    #[test]
+    fn build_rejects_invalid_user_agent() {
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .user_agent("agent\n")
+                .build(),
+        );
+    }
+
+    #[test]
+    fn build_rejects_zero_timeout() {
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .timeout(Duration::ZERO)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn build_rejects_invalid_proxy() {
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .proxy(Url::parse("ftp://localhost").unwrap())
+                .build(),
+        );
+    }
+
+    #[test]
+    fn build_rejects_invalid_certificate() {
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .add_root_certificate("not a certificate")
+                .build(),
+        );
+    }
+
+    #[test]
+    fn build_rejects_transport_settings_with_custom_client() {
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .timeout(Duration::from_secs(1))
+                .http_client(MockClient {
+                    req_received: return_ok,
+                })
+                .build(),
+        );
+        assert_configuration_error(
+            DomeneshopClient::builder()
+                .credentials("token", "secret")
+                .tls_backend(TlsBackend::NativeTls)
+                .http_client(MockClient {
+                    req_received: return_ok,
+                })
+                .build(),
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{credentials::Credentials, errors::DomeneshopError};
+
+use super::{DomeneshopClient, DomeneshopClientConfiguration};
+
+/// Environment variable containing the API token
+pub const TOKEN_ENV: &str = "DOMENESHOP_TOKEN";
+/// Environment variable containing the API secret
+pub const SECRET_ENV: &str = "DOMENESHOP_SECRET";
+/// Environment variable overriding the base url of the API
+pub const BASE_URL_ENV: &str = "DOMENESHOP_BASE_URL";
+
+/// Credentials and settings read from the environment or a config file
+#[derive(Debug)]
+pub(crate) struct LoadedSettings {
+    pub(crate) credentials: Credentials,
+    pub(crate) base_url: Option<String>,
+}
+
+impl LoadedSettings {
+    /// Returns `base_url` if set, otherwise the loaded base url
+    pub(crate) fn base_url_or(&self, base_url: Option<String>) -> Option<String> {
+        base_url.or_else(|| self.base_url.clone())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Profile {
+    token: String,
+    secret: String,
+    base_url: Option<String>,
+}
+
+impl Drop for Profile {
+    fn drop(&mut self) {
+        self.token.zeroize();
+        self.secret.zeroize();
+    }
+}
+
+impl DomeneshopClient {
+    /// Creates a new client using credentials from the environment.
+    ///
+    /// Reads the token from `DOMENESHOP_TOKEN` and the secret from `DOMENESHOP_SECRET`.
+    /// The base url can be overridden with `DOMENESHOP_BASE_URL`.
+    pub fn from_env() -> Result<Self, DomeneshopError> {
+        DomeneshopClient::from_env_with_configuration(DomeneshopClientConfiguration::default())
+    }
+
+    /// Creates a new client using credentials from the environment, see [`from_env`](DomeneshopClient::from_env).
+    ///
+    /// `DOMENESHOP_BASE_URL` is only used if the configuration does not set a base url.
+    pub fn from_env_with_configuration(
+        mut configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        let settings = load_env(|key| std::env::var(key).ok())?;
+        configuration.base_url = settings.base_url_or(configuration.base_url.take());
+        DomeneshopClient::with_credentials(settings.credentials, configuration)
+    }
+
+    /// Creates a new client using credentials from the given profile in the default config file.
+    ///
+    /// The config file is read from `$XDG_CONFIG_HOME/domeneshop/config.toml`,
+    /// falling back to `~/.config/domeneshop/config.toml`.
+    /// Every profile is a TOML table containing `token`, `secret` and an optional `base_url`:
+    ///
+    /// ```toml
+    /// [default]
+    /// token = "<token>"
+    /// secret = "<secret>"
+    ///
+    /// [staging]
+    /// token = "<token>"
+    /// secret = "<secret>"
+    /// base_url = "https://staging.example.com"
+    /// ```
+    ///
+    /// The file is not read if it is readable by everyone.
+    pub fn from_config_file(profile: &str) -> Result<Self, DomeneshopError> {
+        DomeneshopClient::from_config_file_with_configuration(
+            default_config_path()?,
+            profile,
+            DomeneshopClientConfiguration::default(),
+        )
+    }
+
+    /// Creates a new client using credentials from the given profile in the config file at `path`,
+    /// see [`from_config_file`](DomeneshopClient::from_config_file).
+    ///
+    /// The `base_url` of the profile is only used if the configuration does not set a base url.
+    pub fn from_config_file_with_configuration(
+        path: impl AsRef<Path>,
+        profile: &str,
+        mut configuration: DomeneshopClientConfiguration,
+    ) -> Result<Self, DomeneshopError> {
+        let settings = load_config_file(path.as_ref(), profile)?;
+        configuration.base_url = settings.base_url_or(configuration.base_url.take());
+        DomeneshopClient::with_credentials(settings.credentials, configuration)
+    }
+}
+
+/// Reads credentials from the environment, using `var` to look up variables
+pub(crate) fn load_env<F>(var: F) -> Result<LoadedSettings, DomeneshopError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let required = |key: &str| {
+        var(key).filter(|value| !value.is_empty()).ok_or_else(|| {
+            DomeneshopError::configuration(format!("Environment variable {} is not set", key))
+        })
+    };
+
+    Ok(LoadedSettings {
+        credentials: Credentials::new(required(TOKEN_ENV)?, required(SECRET_ENV)?),
+        base_url: var(BASE_URL_ENV).filter(|value| !value.is_empty()),
+    })
+}
+
+/// The default location of the config file
+pub(crate) fn default_config_path() -> Result<PathBuf, DomeneshopError> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok_or_else(|| {
+            DomeneshopError::configuration(
+                "Could not find the config directory. Set XDG_CONFIG_HOME or HOME",
+            )
+        })?;
+
+    Ok(config_dir.join("domeneshop").join("config.toml"))
+}
+
+/// Reads credentials from a profile in the config file at `path`
+pub(crate) fn load_config_file(
+    path: &Path,
+    profile: &str,
+) -> Result<LoadedSettings, DomeneshopError> {
+    let display = path.display();
+    let metadata = fs::metadata(path).map_err(|err| {
+        DomeneshopError::configuration(format!("Could not read config file {}: {}", display, err))
+            .with_source(err)
+    })?;
+    ensure_not_world_readable(path, &metadata)?;
+
+    let content = fs::read_to_string(path)
+        .map(Zeroizing::new)
+        .map_err(|err| {
+            DomeneshopError::configuration(format!(
+                "Could not read config file {}: {}",
+                display, err
+            ))
+            .with_source(err)
+        })?;
+    // The error of the parser is not included, as it quotes the offending line which may contain a secret
+    let mut profiles: BTreeMap<String, Profile> = toml::from_str(&content).map_err(|err| {
+        let line = err
+            .span()
+            .map(|span| content[..span.start].lines().count().max(1));
+        DomeneshopError::configuration(match line {
+            Some(line) => format!("Invalid config file {} at line {}", display, line),
+            None => format!("Invalid config file {}", display),
+        })
+    })?;
+
+    let mut profile = profiles.remove(profile).ok_or_else(|| {
+        DomeneshopError::configuration(format!(
+            "Profile {} not found in {}. Available profiles: {}",
+            profile,
+            display,
+            profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+        ))
+    })?;
+
+    Ok(LoadedSettings {
+        credentials: Credentials::new(
+            std::mem::take(&mut profile.token),
+            std::mem::take(&mut profile.secret),
+        ),
+        base_url: profile.base_url.take(),
+    })
+}
+
+#[cfg(unix)]
+fn ensure_not_world_readable(path: &Path, metadata: &fs::Metadata) -> Result<(), DomeneshopError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = metadata.permissions().mode();
+    if mode & 0o004 != 0 {
+        return Err(DomeneshopError::configuration(format!(
+            "Config file {} is readable by everyone (mode {:o}). Restrict it with `chmod 600`",
+            path.display(),
+            mode & 0o777
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn ensure_not_world_readable(
+    _path: &Path,
+    _metadata: &fs::Metadata,
+) -> Result<(), DomeneshopError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, path::PathBuf};
+
+    use crate::errors::DomeneshopErrorKind;
+
+    use super::{load_config_file, load_env};
+
+    const CONFIG: &str = r#"
+[default]
+token = "token"
+secret = "secret"
+
+[staging]
+token = "staging-token"
+secret = "staging-secret"
+base_url = "https://staging.local"
+"#;
+
+    fn write_config(name: &str, content: &str, mode: u32) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "domeneshop-client-{}-{}.toml",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, content).unwrap();
+        set_mode(&path, mode);
+        path
+    }
+
+    #[cfg(unix)]
+    fn set_mode(path: &PathBuf, mode: u32) {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[cfg(not(unix))]
+    fn set_mode(_path: &PathBuf, _mode: u32) {}
+
+    #[test]
+    fn env_reads_credentials_and_base_url() {
+        let vars = HashMap::from([
+            ("DOMENESHOP_TOKEN", "token"),
+            ("DOMENESHOP_SECRET", "secret"),
+            ("DOMENESHOP_BASE_URL", "https://test.local"),
+        ]);
+
+        let settings = load_env(|key| vars.get(key).map(|value| value.to_string())).unwrap();
+
+        assert_eq!(settings.credentials.token(), "token");
+        assert_eq!(settings.credentials.secret(), "secret");
+        assert_eq!(settings.base_url, Some("https://test.local".to_string()));
+    }
+
+    #[test]
+    fn env_requires_secret() {
+        let vars = HashMap::from([("DOMENESHOP_TOKEN", "token"), ("DOMENESHOP_SECRET", "")]);
+
+        let err = load_env(|key| vars.get(key).map(|value| value.to_string())).unwrap_err();
+
+        assert_eq!(err.kind(), &DomeneshopErrorKind::Configuration);
+        assert!(err.to_string().contains("DOMENESHOP_SECRET"));
+    }
+
+    #[test]
+    fn config_file_reads_profile() {
+        let path = write_config("profile", CONFIG, 0o600);
+
+        let default = load_config_file(&path, "default").unwrap();
+        let staging = load_config_file(&path, "staging").unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(default.credentials.token(), "token");
+        assert_eq!(default.base_url, None);
+        assert_eq!(staging.credentials.secret(), "staging-secret");
+        assert_eq!(staging.base_url, Some("https://staging.local".to_string()));
+    }
+
+    #[test]
+    fn config_file_reports_missing_profile() {
+        let path = write_config("missing", CONFIG, 0o600);
+
+        let err = load_config_file(&path, "production").unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), &DomeneshopErrorKind::Configuration);
+        assert!(err.to_string().contains("default, staging"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn config_file_is_refused_when_world_readable() {
+        let path = write_config("readable", CONFIG, 0o644);
+
+        let err = load_config_file(&path, "default").unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), &DomeneshopErrorKind::Configuration);
+        assert!(err.to_string().contains("readable by everyone"));
+    }
+
+    #[test]
+    fn config_file_rejects_unknown_fields() {
+        let path = write_config("unknown", "[default]\ntokn = \"a\"\n", 0o600);
+
+        let err = load_config_file(&path, "default").unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), &DomeneshopErrorKind::Configuration);
+    }
+}
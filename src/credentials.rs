@@ -0,0 +1,94 @@
+use std::fmt::{Debug, Display};
+
+use zeroize::Zeroize;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// The token and secret used to authenticate against the API.
+///
+/// The values are zeroized when the credentials are dropped, and are never included in `Debug` or `Display` output.
+/// The `Authorization`-header is only created when a request is sent.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::credentials::Credentials;
+///
+/// let credentials = Credentials::new("token", "secret");
+/// assert_eq!(format!("{:?}", credentials), r#"Credentials { token: "[REDACTED]", secret: "[REDACTED]" }"#);
+/// ```
+#[derive(Clone)]
+pub struct Credentials {
+    token: String,
+    secret: String,
+}
+
+impl Credentials {
+    /// Creates new credentials from an API token and secret
+    pub fn new(token: impl Into<String>, secret: impl Into<String>) -> Self {
+        Credentials {
+            token: token.into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// Returns `true` if either the token or the secret is empty
+    pub fn is_empty(&self) -> bool {
+        self.token.is_empty() || self.secret.is_empty()
+    }
+
+    pub(crate) fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub(crate) fn secret(&self) -> &str {
+        &self.secret
+    }
+}
+
+impl Drop for Credentials {
+    fn drop(&mut self) {
+        self.token.zeroize();
+        self.secret.zeroize();
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("token", &REDACTED)
+            .field("secret", &REDACTED)
+            .finish()
+    }
+}
+
+impl Display for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Credentials;
+
+    #[test]
+    fn debug_and_display_are_redacted() {
+        let credentials = Credentials::new("my-token", "my-secret");
+
+        let debug = format!("{:?} {:#?}", credentials, credentials);
+        let display = credentials.to_string();
+
+        for output in [debug, display] {
+            assert!(!output.contains("my-token"));
+            assert!(!output.contains("my-secret"));
+            assert!(output.contains("[REDACTED]"));
+        }
+    }
+
+    #[test]
+    fn is_empty_checks_both_values() {
+        assert!(Credentials::new("", "secret").is_empty());
+        assert!(Credentials::new("token", "").is_empty());
+        assert!(!Credentials::new("token", "secret").is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+use std::sync::{Arc, Mutex};
+
+use http_types::{Method, Request, Response, StatusCode};
+use url::Url;
+
+use crate::{cache::api_segments, endpoints::dns::DnsId, errors::DomeneshopError};
+
+/// A mutation that was recorded instead of sent, see [`DryRun`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DryRunOperation {
+    /// The method of the request
+    pub method: Method,
+    /// The absolute url of the request, including the query
+    pub url: Url,
+    /// The JSON-body of the request, if any
+    pub body: Option<String>,
+}
+
+/// Records mutations instead of sending them, to see what a script would change.
+///
+/// When set on the client, `add_dns_record`, `update_dns_record`, `delete_dns_record`, `add_forward`,
+/// `update_forward`, `delete_forward` and `update_dyndns` never reach the [`HttpClient`](crate::http::HttpClient).
+/// They are recorded as a [`DryRunOperation`] and succeed with a synthetic response.
+/// Read endpoints are sent as usual.
+///
+/// Added DNS records are given synthetic ids, numbered from 1 in the order the records are added.
+/// These do not match the ids the API would assign.
+///
+/// The dry run is cheap to clone, and all clones share the same recorded operations.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::dry_run::DryRun;
+///
+/// let dry_run = DryRun::new();
+/// // Pass a clone to the client, then inspect the operations afterwards
+/// assert!(dry_run.operations().is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DryRun {
+    state: Arc<Mutex<DryRunState>>,
+}
+
+#[derive(Debug, Default)]
+struct DryRunState {
+    operations: Vec<DryRunOperation>,
+    last_id: DnsId,
+}
+
+impl DryRun {
+    /// Creates a dry run without any recorded operations
+    pub fn new() -> Self {
+        DryRun::default()
+    }
+
+    /// Returns the operations recorded so far, in the order they were made
+    pub fn operations(&self) -> Vec<DryRunOperation> {
+        self.state.lock().unwrap().operations.clone()
+    }
+
+    /// Removes all recorded operations
+    pub fn clear(&self) {
+        self.state.lock().unwrap().operations.clear();
+    }
+
+    /// Records the request if it is a mutation, returning the synthetic response
+    pub(crate) async fn intercept(
+        &self,
+        request: &mut Request,
+    ) -> Result<Option<Response>, DomeneshopError> {
+        let url = request.url().clone();
+        let segments = api_segments(&url);
+        let is_dyndns = segments == ["dyndns", "update"];
+        if request.method() == Method::Get && !is_dyndns {
+            return Ok(None);
+        }
+
+        let body = request.take_body().into_string().await.map_err(|err| {
+            DomeneshopError::new("Failed to read request body").with_source(err.into_inner())
+        })?;
+        let mut state = self.state.lock().unwrap();
+        state.operations.push(DryRunOperation {
+            method: request.method(),
+            url: url.clone(),
+            body: (!body.is_empty()).then_some(body),
+        });
+
+        let response = match (request.method(), segments.as_slice()) {
+            (Method::Post, ["domains", _, "dns"]) => {
+                state.last_id += 1;
+                let mut response = Response::new(StatusCode::Created);
+                response.insert_header(
+                    "Location",
+                    format!("{}/{}", url.as_str().trim_end_matches('/'), state.last_id),
+                );
+                response.set_body(format!("{{\"id\": {}}}", state.last_id));
+                response
+            }
+            (Method::Post, _) => Response::new(StatusCode::Created),
+            (Method::Put, ["domains", _, "forwards", _]) => {
+                let mut response = Response::new(StatusCode::Ok);
+                if let Some(body) = &state.operations.last().unwrap().body {
+                    response.set_body(body.as_str());
+                }
+                response
+            }
+            _ => Response::new(StatusCode::NoContent),
+        };
+        Ok(Some(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_types::{Method, Request, StatusCode};
+
+    use super::DryRun;
+
+    fn request(method: Method, path: &str) -> Request {
+        Request::new(
+            method,
+            format!("https://api.domeneshop.no/v0{}", path).as_str(),
+        )
+    }
+
+    #[test]
+    fn reads_are_not_intercepted() {
+        let dry_run = DryRun::new();
+
+        let response =
+            tokio_test::block_on(dry_run.intercept(&mut request(Method::Get, "/domains/1/dns")))
+                .unwrap();
+
+        assert!(response.is_none());
+        assert!(dry_run.operations().is_empty());
+    }
+
+    #[test]
+    fn added_records_get_sequential_ids() {
+        let dry_run = DryRun::new();
+        let mut first = request(Method::Post, "/domains/1/dns");
+        first.set_body("{}");
+
+        let mut first = tokio_test::block_on(dry_run.intercept(&mut first))
+            .unwrap()
+            .unwrap();
+        let mut second =
+            tokio_test::block_on(dry_run.intercept(&mut request(Method::Post, "/domains/1/dns")))
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(first.status(), StatusCode::Created);
+        assert_eq!(
+            tokio_test::block_on(first.body_string()).unwrap(),
+            "{\"id\": 1}"
+        );
+        assert_eq!(
+            second.header("Location").unwrap().as_str(),
+            "https://api.domeneshop.no/v0/domains/1/dns/2"
+        );
+        assert_eq!(
+            tokio_test::block_on(second.body_string()).unwrap(),
+            "{\"id\": 2}"
+        );
+        assert_eq!(dry_run.operations()[0].body.as_deref(), Some("{}"));
+        assert_eq!(dry_run.operations()[1].body, None);
+    }
+
+    #[test]
+    fn dyndns_is_intercepted() {
+        let dry_run = DryRun::new();
+
+        let response = tokio_test::block_on(dry_run.intercept(&mut request(
+            Method::Get,
+            "/dyndns/update?hostname=www.example.com",
+        )))
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NoContent);
+        assert_eq!(
+            dry_run.operations()[0].url.query(),
+            Some("hostname=www.example.com")
+        );
+    }
+}
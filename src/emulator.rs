@@ -0,0 +1,152 @@
+use std::{convert::Infallible, future::Future, net::TcpListener};
+
+use http_types::{Method, Request, Url};
+use hyper::{
+    header::HOST,
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn},
+    Body, Server, StatusCode,
+};
+
+use crate::{errors::DomeneshopError, http::HttpClient, http_client::fake::FakeDomeneshop};
+
+/// A local HTTP server emulating the Domeneshop API, backed by a [`FakeDomeneshop`].
+///
+/// The emulator serves the `/v0`-routes of the API, and its address can be used as `base_url` in
+/// [`DomeneshopClientConfiguration`](crate::client::DomeneshopClientConfiguration).
+/// Requests to the dyndns-endpoint without an IP use the address of the connecting client.
+///
+/// The `domeneshop-emulator` binary wraps this for use outside of Rust.
+///
+/// # Example
+/// ```no_run
+/// use std::net::TcpListener;
+/// use domeneshop_client::emulator::Emulator;
+/// use domeneshop_client::http_client::fake::{FakeDomeneshop, FakeState};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let fake = FakeDomeneshop::from_state(FakeState::load("fixture.json")?)
+///     .with_credentials("token", "secret");
+/// let listener = TcpListener::bind("127.0.0.1:0")?;
+/// println!("Listening on http://{}", listener.local_addr()?);
+///
+/// Emulator::new(fake).serve(listener).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Emulator {
+    fake: FakeDomeneshop,
+}
+
+impl Emulator {
+    /// Creates an emulator serving the given fake
+    pub fn new(fake: FakeDomeneshop) -> Self {
+        Emulator { fake }
+    }
+
+    /// Returns the fake backing the emulator. Clones share state with the emulator.
+    pub fn fake(&self) -> &FakeDomeneshop {
+        &self.fake
+    }
+
+    /// Serves requests on the listener until the server fails
+    pub async fn serve(self, listener: TcpListener) -> Result<(), DomeneshopError> {
+        self.serve_with_shutdown(listener, std::future::pending())
+            .await
+    }
+
+    /// Serves requests on the listener until `signal` completes
+    pub async fn serve_with_shutdown(
+        self,
+        listener: TcpListener,
+        signal: impl Future<Output = ()>,
+    ) -> Result<(), DomeneshopError> {
+        let fake = self.fake;
+        let make_service = make_service_fn(move |connection: &AddrStream| {
+            let fake = fake.clone().with_client_ip(connection.remote_addr().ip());
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let fake = fake.clone();
+                    async move { Ok::<_, Infallible>(handle(&fake, request).await) }
+                }))
+            }
+        });
+
+        Server::from_tcp(listener)
+            .map_err(|err| {
+                DomeneshopError::new(format!("Could not listen: {}", err)).with_source(err)
+            })?
+            .serve(make_service)
+            .with_graceful_shutdown(signal)
+            .await
+            .map_err(|err| DomeneshopError::new(format!("Server failed: {}", err)).with_source(err))
+    }
+}
+
+async fn handle(fake: &FakeDomeneshop, request: hyper::Request<Body>) -> hyper::Response<Body> {
+    match forward(fake, request).await {
+        Ok(response) => response,
+        Err(err) => {
+            let mut response = hyper::Response::new(Body::from(err.to_string()));
+            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+            response
+        }
+    }
+}
+
+/// Converts the request to `http-types`, lets the fake handle it, and converts the response back
+async fn forward(
+    fake: &FakeDomeneshop,
+    request: hyper::Request<Body>,
+) -> Result<hyper::Response<Body>, DomeneshopError> {
+    let (parts, body) = request.into_parts();
+    let host = parts
+        .headers
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    let url = Url::parse(&format!("http://{}{}", host, path))
+        .map_err(|err| DomeneshopError::new(format!("Invalid url: {}", err)).with_source(err))?;
+    let method: Method = parts
+        .method
+        .as_str()
+        .parse()
+        .map_err(|err: http_types::Error| {
+            DomeneshopError::new(format!("Invalid method: {}", err)).with_source(err.into_inner())
+        })?;
+
+    let mut request = Request::new(method, url);
+    for (name, value) in &parts.headers {
+        if let Ok(value) = value.to_str() {
+            request.append_header(name.as_str(), value);
+        }
+    }
+    let body = hyper::body::to_bytes(body).await.map_err(|err| {
+        DomeneshopError::new(format!("Could not read request body: {}", err)).with_source(err)
+    })?;
+    if !body.is_empty() {
+        request.set_body(body.to_vec());
+    }
+
+    let mut response = fake.execute_request(request).await?;
+    let mut builder = hyper::Response::builder().status(u16::from(response.status()));
+    for (name, values) in response.iter() {
+        for value in values.iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+    }
+    let body = response.take_body().into_bytes().await.map_err(|err| {
+        DomeneshopError::new(format!("Could not read response body: {}", err))
+            .with_source(err.into_inner())
+    })?;
+    builder.body(Body::from(body)).map_err(|err| {
+        DomeneshopError::new(format!("Could not build response: {}", err)).with_source(err)
+    })
+}
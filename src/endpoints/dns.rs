@@ -1,16 +1,26 @@
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Display, Formatter},
+    net::{Ipv4Addr, Ipv6Addr},
+};
 
 use http_types::{Method, Request, Response, StatusCode};
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use url::Url;
 
 use crate::{
     client::{set_body, DomeneshopClient},
-    errors::{to_domain_error_with_context, DomeneshopError},
+    errors::{to_deserialization_error_with_context, DomeneshopError},
+    http::HttpClient,
 };
 
 use super::domains::DomainId;
 
+mod validation;
+
+pub(crate) use validation::{txt_strings, MAX_TXT_STRING_LENGTH};
+pub use validation::{RecordField, RecordViolation};
+
 /// Enum representing a type of DNS record
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
 pub enum DnsType {
@@ -26,6 +36,16 @@ pub enum DnsType {
     SRV,
     /// TXT-Record
     TXT,
+    /// ANAME-Record
+    ANAME,
+    /// CAA-Record
+    CAA,
+    /// NS-Record
+    NS,
+    /// DS-Record
+    DS,
+    /// TLSA-Record
+    TLSA,
 }
 
 impl Display for DnsType {
@@ -38,6 +58,11 @@ impl Display for DnsType {
                 DnsType::MX => "MX",
                 DnsType::SRV => "SRV",
                 DnsType::TXT => "TXT",
+                DnsType::ANAME => "ANAME",
+                DnsType::CAA => "CAA",
+                DnsType::NS => "NS",
+                DnsType::DS => "DS",
+                DnsType::TLSA => "TLSA",
             },
             f,
         )
@@ -73,6 +98,171 @@ pub enum DnsRecordData {
     SRV(SRVRecordData),
     /// TXT-Record
     TXT(TXTRecordData),
+    /// ANAME-Record
+    ANAME(ANAMERecordData),
+    /// CAA-Record
+    CAA(CAARecordData),
+    /// NS-Record
+    NS(NSRecordData),
+    /// DS-Record
+    DS(DSRecordData),
+    /// TLSA-Record
+    TLSA(TLSARecordData),
+}
+
+impl DnsRecordData {
+    /// The type of the record
+    pub fn dns_type(&self) -> DnsType {
+        match self {
+            DnsRecordData::A(_) => DnsType::A,
+            DnsRecordData::AAAA(_) => DnsType::AAAA,
+            DnsRecordData::CNAME(_) => DnsType::CNAME,
+            DnsRecordData::MX(_) => DnsType::MX,
+            DnsRecordData::SRV(_) => DnsType::SRV,
+            DnsRecordData::TXT(_) => DnsType::TXT,
+            DnsRecordData::ANAME(_) => DnsType::ANAME,
+            DnsRecordData::CAA(_) => DnsType::CAA,
+            DnsRecordData::NS(_) => DnsType::NS,
+            DnsRecordData::DS(_) => DnsType::DS,
+            DnsRecordData::TLSA(_) => DnsType::TLSA,
+        }
+    }
+
+    /// The host/subdomain the record applies to
+    pub fn host(&self) -> &str {
+        match self {
+            DnsRecordData::A(record) => &record.host,
+            DnsRecordData::AAAA(record) => &record.host,
+            DnsRecordData::CNAME(record) => &record.host,
+            DnsRecordData::MX(record) => &record.host,
+            DnsRecordData::SRV(record) => &record.host,
+            DnsRecordData::TXT(record) => &record.host,
+            DnsRecordData::ANAME(record) => &record.host,
+            DnsRecordData::CAA(record) => &record.host,
+            DnsRecordData::NS(record) => &record.host,
+            DnsRecordData::DS(record) => &record.host,
+            DnsRecordData::TLSA(record) => &record.host,
+        }
+    }
+
+    /// TTL of the record
+    pub fn ttl(&self) -> Ttl {
+        match self {
+            DnsRecordData::A(record) => record.ttl,
+            DnsRecordData::AAAA(record) => record.ttl,
+            DnsRecordData::CNAME(record) => record.ttl,
+            DnsRecordData::MX(record) => record.ttl,
+            DnsRecordData::SRV(record) => record.ttl,
+            DnsRecordData::TXT(record) => record.ttl,
+            DnsRecordData::ANAME(record) => record.ttl,
+            DnsRecordData::CAA(record) => record.ttl,
+            DnsRecordData::NS(record) => record.ttl,
+            DnsRecordData::DS(record) => record.ttl,
+            DnsRecordData::TLSA(record) => record.ttl,
+        }
+    }
+
+    /// The data of the record, e.g. the IP address of an A-record
+    pub fn data(&self) -> Cow<'_, str> {
+        match self {
+            DnsRecordData::A(record) => Cow::Owned(record.data.to_string()),
+            DnsRecordData::AAAA(record) => Cow::Owned(record.data.to_string()),
+            DnsRecordData::CNAME(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::MX(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::SRV(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::TXT(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::ANAME(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::CAA(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::NS(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::DS(record) => Cow::Borrowed(&record.data),
+            DnsRecordData::TLSA(record) => Cow::Borrowed(&record.data),
+        }
+    }
+}
+
+/// TTL of a DNS record.
+///
+/// The API requires a multiple of 60 seconds, from one minute up to one week.
+/// Serialized as a number of seconds, and also deserialized from a string.
+///
+/// Deserialization accepts any number of seconds, so records the API returns with other TTLs can still be read.
+/// Use [`DnsRecordData::validate`] to check the TTL of such records.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::endpoints::dns::Ttl;
+///
+/// let ttl = Ttl::new(3600).unwrap();
+/// assert_eq!(ttl, Ttl::HOUR);
+/// assert!(Ttl::new(90).is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Ttl(u32);
+
+impl Ttl {
+    /// The lowest TTL accepted, one minute
+    pub const MIN: Ttl = Ttl(60);
+    /// The highest TTL accepted, one week
+    pub const MAX: Ttl = Ttl(604_800);
+    /// One hour
+    pub const HOUR: Ttl = Ttl(3600);
+    /// One day
+    pub const DAY: Ttl = Ttl(86_400);
+
+    /// Creates a TTL of `seconds`. Fails if it is not a multiple of 60 between [`Ttl::MIN`] and [`Ttl::MAX`].
+    pub fn new(seconds: u32) -> Result<Ttl, DomeneshopError> {
+        if seconds % 60 != 0 || !(Ttl::MIN.0..=Ttl::MAX.0).contains(&seconds) {
+            return Err(DomeneshopError::new(format!(
+                "Invalid TTL {}: must be a multiple of 60 between {} and {} seconds",
+                seconds,
+                Ttl::MIN.0,
+                Ttl::MAX.0
+            )));
+        }
+        Ok(Ttl(seconds))
+    }
+
+    /// Creates a TTL of `seconds` without checking it, like when deserializing
+    pub(crate) fn unchecked(seconds: u32) -> Ttl {
+        Ttl(seconds)
+    }
+
+    /// The TTL in seconds
+    pub fn seconds(self) -> u32 {
+        self.0
+    }
+}
+
+impl TryFrom<u32> for Ttl {
+    type Error = DomeneshopError;
+
+    fn try_from(seconds: u32) -> Result<Self, Self::Error> {
+        Ttl::new(seconds)
+    }
+}
+
+impl From<Ttl> for u32 {
+    fn from(ttl: Ttl) -> Self {
+        ttl.0
+    }
+}
+
+impl Display for Ttl {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Serialize for Ttl {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ttl {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        number_or_string(deserializer).map(Ttl)
+    }
 }
 
 /// Represents data about an A-record
@@ -80,10 +270,10 @@ pub enum DnsRecordData {
 pub struct ARecordData {
     /// The host/subdomain the DNS record applies to
     pub host: String,
-    /// TTL of DNS record in seconds. Must be a multiple of 60.
-    pub ttl: i16,
+    /// TTL of DNS record
+    pub ttl: Ttl,
     /// IPv4 address
-    pub data: String,
+    pub data: Ipv4Addr,
 }
 
 /// Represents data about an AAAA-record
@@ -91,10 +281,10 @@ pub struct ARecordData {
 pub struct AAAARecordData {
     /// The host/subdomain the DNS record applies to
     pub host: String,
-    /// TTL of DNS record in seconds. Must be a multiple of 60.
-    pub ttl: i16,
+    /// TTL of DNS record
+    pub ttl: Ttl,
     /// IPv6 address
-    pub data: String,
+    pub data: Ipv6Addr,
 }
 
 /// Represents data about a CNAME-record
@@ -102,8 +292,8 @@ pub struct AAAARecordData {
 pub struct CNAMERecordData {
     /// The host/subdomain the DNS record applies to
     pub host: String,
-    /// TTL of DNS record in seconds. Must be a multiple of 60.
-    pub ttl: i16,
+    /// TTL of DNS record
+    pub ttl: Ttl,
     /// The target hostname
     pub data: String,
 }
@@ -113,12 +303,13 @@ pub struct CNAMERecordData {
 pub struct MXRecordData {
     /// The host/subdomain the DNS record applies to
     pub host: String,
-    /// TTL of DNS record in seconds. Must be a multiple of 60.
-    pub ttl: i16,
+    /// TTL of DNS record
+    pub ttl: Ttl,
     /// The target MX host.    
     pub data: String,
     /// MX record priority, also known as preference. Lower values are usually preferred first, but this is not guaranteed
-    pub priority: String,
+    #[serde(serialize_with = "as_string", deserialize_with = "number_or_string")]
+    pub priority: u16,
 }
 
 /// Represents data about a SRV-record
@@ -126,16 +317,19 @@ pub struct MXRecordData {
 pub struct SRVRecordData {
     /// The host/subdomain the DNS record applies to
     pub host: String,
-    /// TTL of DNS record in seconds. Must be a multiple of 60.
-    pub ttl: i16,
+    /// TTL of DNS record
+    pub ttl: Ttl,
     /// The target hostname
     pub data: String,
     /// SRV record priority, also known as preference. Lower values are usually preferred first
-    pub priority: String,
+    #[serde(serialize_with = "as_string", deserialize_with = "number_or_string")]
+    pub priority: u16,
     /// SRV record weight. Relevant if multiple records have same preference
-    pub weight: String,
+    #[serde(serialize_with = "as_string", deserialize_with = "number_or_string")]
+    pub weight: u16,
     /// SRV record port. The port where the service is found.
-    pub port: String,
+    #[serde(serialize_with = "as_string", deserialize_with = "number_or_string")]
+    pub port: u16,
 }
 
 /// Represents data about a TXT-record
@@ -143,12 +337,170 @@ pub struct SRVRecordData {
 pub struct TXTRecordData {
     /// The host/subdomain the DNS record applies to
     pub host: String,
-    /// TTL of DNS record in seconds. Must be a multiple of 60.
-    pub ttl: i16,
+    /// TTL of DNS record
+    pub ttl: Ttl,
     /// Freeform text field
     pub data: String,
 }
 
+/// Represents data about an ANAME-record
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub struct ANAMERecordData {
+    /// The host/subdomain the DNS record applies to
+    pub host: String,
+    /// TTL of DNS record
+    pub ttl: Ttl,
+    /// The target hostname, whose addresses are served for the host
+    pub data: String,
+}
+
+/// Represents data about a CAA-record
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub struct CAARecordData {
+    /// The host/subdomain the DNS record applies to
+    pub host: String,
+    /// TTL of DNS record
+    pub ttl: Ttl,
+    /// The value of the property, e.g. the domain of the certificate authority for `issue`
+    pub data: String,
+    /// CAA flags. `128` marks the property as critical, otherwise `0`
+    #[serde(deserialize_with = "number_or_string")]
+    pub flags: u8,
+    /// The property of the record
+    pub tag: CAATag,
+}
+
+/// The property of a CAA-record
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CAATag {
+    /// Authorizes a certificate authority to issue certificates for the host
+    Issue,
+    /// Authorizes a certificate authority to issue wildcard certificates for the host
+    IssueWild,
+    /// Where certificate authorities report policy violations
+    Iodef,
+}
+
+impl Display for CAATag {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(
+            match self {
+                CAATag::Issue => "issue",
+                CAATag::IssueWild => "issuewild",
+                CAATag::Iodef => "iodef",
+            },
+            f,
+        )
+    }
+}
+
+// The API represents the tag as a number: 0 = issue, 1 = issuewild, 2 = iodef
+impl Serialize for CAATag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(match self {
+            CAATag::Issue => 0,
+            CAATag::IssueWild => 1,
+            CAATag::Iodef => 2,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for CAATag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Tag {
+            Number(u8),
+            Name(String),
+        }
+        match Tag::deserialize(deserializer)? {
+            Tag::Number(0) => Ok(CAATag::Issue),
+            Tag::Number(1) => Ok(CAATag::IssueWild),
+            Tag::Number(2) => Ok(CAATag::Iodef),
+            Tag::Name(name) if name.eq_ignore_ascii_case("issue") => Ok(CAATag::Issue),
+            Tag::Name(name) if name.eq_ignore_ascii_case("issuewild") => Ok(CAATag::IssueWild),
+            Tag::Name(name) if name.eq_ignore_ascii_case("iodef") => Ok(CAATag::Iodef),
+            Tag::Number(number) => Err(de::Error::custom(format!("Unknown CAA tag {}", number))),
+            Tag::Name(name) => Err(de::Error::custom(format!("Unknown CAA tag {}", name))),
+        }
+    }
+}
+
+/// Represents data about an NS-record
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub struct NSRecordData {
+    /// The host/subdomain the DNS record applies to. NS-records cannot be added for the root domain.
+    pub host: String,
+    /// TTL of DNS record
+    pub ttl: Ttl,
+    /// The hostname of the name server the host is delegated to
+    pub data: String,
+}
+
+/// Represents data about a DS-record
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub struct DSRecordData {
+    /// The host/subdomain the DNS record applies to
+    pub host: String,
+    /// TTL of DNS record
+    pub ttl: Ttl,
+    /// The digest of the DNSKEY, in hex
+    pub data: String,
+    /// Key tag of the DNSKEY the record refers to
+    #[serde(deserialize_with = "number_or_string")]
+    pub tag: u16,
+    /// The algorithm of the DNSKEY, e.g. `13` for ECDSA P-256 with SHA-256
+    #[serde(deserialize_with = "number_or_string")]
+    pub alg: u8,
+    /// The algorithm of the digest, e.g. `2` for SHA-256
+    #[serde(deserialize_with = "number_or_string")]
+    pub digest: u8,
+}
+
+/// Represents data about a TLSA-record
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+pub struct TLSARecordData {
+    /// The host/subdomain the DNS record applies to, e.g. `_443._tcp.www`
+    pub host: String,
+    /// TTL of DNS record
+    pub ttl: Ttl,
+    /// The certificate association data, in hex
+    pub data: String,
+    /// Certificate usage. `0` = PKIX-TA, `1` = PKIX-EE, `2` = DANE-TA, `3` = DANE-EE
+    #[serde(deserialize_with = "number_or_string")]
+    pub usage: u8,
+    /// Selector. `0` = full certificate, `1` = public key
+    #[serde(deserialize_with = "number_or_string")]
+    pub selector: u8,
+    /// Matching type. `0` = exact match, `1` = SHA-256, `2` = SHA-512
+    #[serde(deserialize_with = "number_or_string")]
+    pub dtype: u8,
+}
+
+/// Serializes a number as a string, which is what the API expects for priority, weight and port
+fn as_string<S: Serializer>(value: &u16, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+/// Deserializes a number that may be represented as a string
+fn number_or_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + std::str::FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString<T> {
+        Number(T),
+        String(String),
+    }
+    match NumberOrString::<T>::deserialize(deserializer)? {
+        NumberOrString::Number(number) => Ok(number),
+        NumberOrString::String(string) => string.trim().parse().map_err(de::Error::custom),
+    }
+}
+
 /// Response when adding a new DNS record to a domain
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
 pub struct AddDnsRecordResponse {
@@ -164,8 +516,16 @@ struct DomeneshopAddDnsRecordResponse {
 }
 
 /// Operations concerning DNS Records
-impl DomeneshopClient {
+impl<H: HttpClient> DomeneshopClient<H> {
     /// Get DNS Record by id
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn get_dns_record(
         &self,
         domain_id: DomainId,
@@ -177,6 +537,14 @@ impl DomeneshopClient {
     }
 
     /// Lists all DNS records for a domain
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn list_dns_records(
         &self,
         domain_id: DomainId,
@@ -187,6 +555,14 @@ impl DomeneshopClient {
     }
 
     /// Lists filtered subset of DNS records for a domain
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn list_dns_records_with_filter(
         &self,
         domain_id: DomainId,
@@ -207,11 +583,22 @@ impl DomeneshopClient {
     }
 
     /// adds a new  DNS record for the given domain
+    ///
+    /// The record is validated with [`DnsRecordData::validate`] before it is sent.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, record),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn add_dns_record(
         &self,
         domain_id: DomainId,
         record: DnsRecordData,
     ) -> Result<AddDnsRecordResponse, DomeneshopError> {
+        validation::ensure_valid(&record)?;
         let url = self.create_url(format!("/domains/{}/dns", domain_id))?;
 
         let mut request = Request::new(Method::Post, url);
@@ -225,7 +612,7 @@ impl DomeneshopClient {
                     .body_json::<DomeneshopAddDnsRecordResponse>()
                     .await
                     .map_err(|err| {
-                        to_domain_error_with_context(
+                        to_deserialization_error_with_context(
                             "DNS record created successfully, but could not deserialize response",
                             err,
                         )
@@ -235,19 +622,33 @@ impl DomeneshopClient {
                     url: location,
                 })
             }
-            _ => Err(DomeneshopError::new(format!(
-                "Encountered unexpected response status {}",
-                response.status()
-            ))),
+            _ => Err(DomeneshopError::unexpected_status(response.status())),
         }
     }
 
     /// Updates an existing DNS record for the given domain
+    ///
+    /// The record is validated with [`DnsRecordData::validate`] before it is sent.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, record),
+            fields(
+                dns_id = record.id,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn update_dns_record(
         &self,
         domain_id: DomainId,
         record: ExistingDnsRecord,
     ) -> Result<(), DomeneshopError> {
+        validation::ensure_valid(&record.data)?;
         let url = self.create_url(format!("/domains/{}/dns/{}", domain_id, record.id))?;
 
         let mut request = Request::new(Method::Put, url);
@@ -256,14 +657,19 @@ impl DomeneshopClient {
         let response = self.send(request).await?;
         match response.status() {
             StatusCode::NoContent => Ok(()),
-            _ => Err(DomeneshopError::new(format!(
-                "Encountered unexpected response status {}",
-                response.status()
-            ))),
+            _ => Err(DomeneshopError::unexpected_status(response.status())),
         }
     }
 
     /// Deletes a dns record using the given id
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn delete_dns_record(
         &self,
         domain_id: DomainId,
@@ -280,9 +686,6 @@ impl DomeneshopClient {
 fn parse_location_header(response: &Response) -> Option<Url> {
     match response.header("Location") {
         None => None,
-        Some(header) => match Url::parse(header.last().as_str()) {
-            Err(_) => None,
-            Ok(url) => Some(url),
-        },
+        Some(header) => Url::parse(header.last().as_str()).ok(),
     }
 }
@@ -0,0 +1,450 @@
+use std::fmt::{self, Display, Formatter};
+
+use url::Host;
+
+use crate::errors::{DomeneshopError, DomeneshopErrorKind};
+
+use super::{DnsRecordData, Ttl};
+
+/// Longest name allowed in DNS, excluding the trailing dot
+const MAX_NAME_LENGTH: usize = 253;
+/// Longest label allowed in DNS
+const MAX_LABEL_LENGTH: usize = 63;
+/// Longest character-string allowed in a TXT-record
+pub(crate) const MAX_TXT_STRING_LENGTH: usize = 255;
+
+/// A field of a DNS record, named as in the API
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RecordField {
+    /// The host/subdomain of the record
+    Host,
+    /// The TTL of the record
+    Ttl,
+    /// The data of the record, e.g. the target hostname
+    Data,
+}
+
+impl Display for RecordField {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(
+            match self {
+                RecordField::Host => "host",
+                RecordField::Ttl => "ttl",
+                RecordField::Data => "data",
+            },
+            f,
+        )
+    }
+}
+
+/// A rule a DNS record breaks, found by [`DnsRecordData::validate`]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RecordViolation {
+    /// The offending field
+    pub field: RecordField,
+    /// Description of what is wrong with the field
+    pub message: String,
+}
+
+impl RecordViolation {
+    fn new(field: RecordField, message: impl Into<String>) -> RecordViolation {
+        RecordViolation {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for RecordViolation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl DnsRecordData {
+    /// Checks the record against the rules of the API without sending it.
+    ///
+    /// This is done automatically by [`add_dns_record`](crate::client::DomeneshopClient::add_dns_record)
+    /// and [`update_dns_record`](crate::client::DomeneshopClient::update_dns_record).
+    /// Returns every violation found, or an empty list if the record is valid.
+    ///
+    /// # Example
+    /// ```
+    /// use domeneshop_client::endpoints::dns::{CNAMERecordData, DnsRecordData, RecordField, Ttl};
+    ///
+    /// let record = DnsRecordData::CNAME(CNAMERecordData {
+    ///     host: "-www".to_string(),
+    ///     ttl: Ttl::HOUR,
+    ///     data: "example.com".to_string(),
+    /// });
+    ///
+    /// let violations = record.validate();
+    /// assert_eq!(violations.len(), 1);
+    /// assert_eq!(violations[0].field, RecordField::Host);
+    /// ```
+    pub fn validate(&self) -> Vec<RecordViolation> {
+        let mut violations = Vec::new();
+
+        match self {
+            DnsRecordData::SRV(record) => validate_srv_host(&record.host, &mut violations),
+            _ => validate_host(self.host(), &mut violations),
+        }
+        validate_ttl(self.ttl(), &mut violations);
+
+        match self {
+            DnsRecordData::CNAME(record) => validate_target(&record.data, false, &mut violations),
+            DnsRecordData::ANAME(record) => validate_target(&record.data, false, &mut violations),
+            DnsRecordData::NS(record) => validate_target(&record.data, false, &mut violations),
+            // A single dot means that the domain does not accept mail (RFC 7505) or offer the service (RFC 2782)
+            DnsRecordData::MX(record) => validate_target(&record.data, true, &mut violations),
+            DnsRecordData::SRV(record) => validate_target(&record.data, true, &mut violations),
+            DnsRecordData::TXT(record) => {
+                if let Err(message) = txt_strings(&record.data) {
+                    violations.push(RecordViolation::new(RecordField::Data, message));
+                }
+            }
+            _ => {}
+        }
+
+        violations
+    }
+}
+
+/// Fails with [`DomeneshopErrorKind::InvalidRecord`] if the record is invalid, so it is not sent
+pub(super) fn ensure_valid(record: &DnsRecordData) -> Result<(), DomeneshopError> {
+    let violations = record.validate();
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let message = violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(
+        DomeneshopError::new(format!("Invalid {}-record: {}", record.dns_type(), message))
+            .with_kind(DomeneshopErrorKind::InvalidRecord(violations)),
+    )
+}
+
+/// Validates a host relative to the domain, where `@` is the domain itself and `*` may be used as a wildcard
+fn validate_host(host: &str, violations: &mut Vec<RecordViolation>) {
+    if host == "@" {
+        return;
+    }
+    let name = host.strip_prefix("*.").unwrap_or(host);
+    if name == "*" {
+        return;
+    }
+    if let Err(message) = validate_name(name) {
+        violations.push(RecordViolation::new(RecordField::Host, message));
+    }
+}
+
+/// SRV-records must be named `_service._proto`, optionally followed by a subdomain
+fn validate_srv_host(host: &str, violations: &mut Vec<RecordViolation>) {
+    let mut labels = host.split('.');
+    let service = labels.next().unwrap_or_default();
+    let protocol = labels.next().unwrap_or_default();
+    if !is_underscore_label(service) || !is_underscore_label(protocol) {
+        violations.push(RecordViolation::new(
+            RecordField::Host,
+            format!(
+                "SRV-record host '{}' must start with _service._proto, e.g. _sip._tcp",
+                host
+            ),
+        ));
+        return;
+    }
+    validate_host(host, violations);
+}
+
+fn is_underscore_label(label: &str) -> bool {
+    label.len() > 1 && label.starts_with('_')
+}
+
+/// Validates that the TTL follows the rules of [`Ttl::new`]. Deserialized TTLs are not checked, so they may break them
+fn validate_ttl(ttl: Ttl, violations: &mut Vec<RecordViolation>) {
+    if let Err(err) = Ttl::new(ttl.seconds()) {
+        violations.push(RecordViolation::new(RecordField::Ttl, err.to_string()));
+    }
+}
+
+/// Validates the hostname a record points to. It may be fully qualified with a trailing dot
+fn validate_target(target: &str, allow_root: bool, violations: &mut Vec<RecordViolation>) {
+    if allow_root && target == "." {
+        return;
+    }
+    let name = target.strip_suffix('.').unwrap_or(target);
+    let result = if name == "@" {
+        Err("Target must be a hostname, '@' is not supported".to_string())
+    } else {
+        validate_name(name)
+    };
+    if let Err(message) = result {
+        violations.push(RecordViolation::new(RecordField::Data, message));
+    }
+}
+
+/// Validates a name made of labels separated by dots. Internationalized labels are checked in their ASCII form
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    let mut length = 0;
+    for label in name.split('.') {
+        let label = to_ascii_label(label)?;
+        validate_label(&label)?;
+        length += label.len() + 1;
+    }
+    if length - 1 > MAX_NAME_LENGTH {
+        return Err(format!(
+            "Name '{}' is longer than {} characters",
+            name, MAX_NAME_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+fn to_ascii_label(label: &str) -> Result<String, String> {
+    if label.is_ascii() {
+        return Ok(label.to_string());
+    }
+    match Host::parse(label) {
+        Ok(Host::Domain(ascii)) if !ascii.contains('.') => Ok(ascii),
+        _ => Err(format!(
+            "Label '{}' is not a valid internationalized name",
+            label
+        )),
+    }
+}
+
+fn validate_label(label: &str) -> Result<(), String> {
+    if label.is_empty() {
+        return Err("Name cannot contain empty labels".to_string());
+    }
+    if label.len() > MAX_LABEL_LENGTH {
+        return Err(format!(
+            "Label '{}' is longer than {} characters",
+            label, MAX_LABEL_LENGTH
+        ));
+    }
+    if label.starts_with('-') || label.ends_with('-') {
+        return Err(format!(
+            "Label '{}' cannot start or end with a hyphen",
+            label
+        ));
+    }
+    match label
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        Some(c) => Err(format!(
+            "Label '{}' contains invalid character '{}'",
+            label, c
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Splits the data of a TXT-record into its character-strings.
+///
+/// Data without quotes is a single string. Longer text must be split into quoted strings,
+/// e.g. `"first part" "second part"`, where `\"` and `\\` escape a quote and a backslash.
+pub(crate) fn txt_strings(data: &str) -> Result<Vec<String>, String> {
+    if data.is_empty() {
+        return Err("TXT-record cannot be empty".to_string());
+    }
+    let strings = if data.trim_start().starts_with('"') {
+        parse_quoted_strings(data)?
+    } else if data.contains('"') {
+        return Err(
+            "Quotes must be escaped inside a quoted string, e.g. \"say \\\"hello\\\"\"".to_string(),
+        );
+    } else {
+        vec![data.to_string()]
+    };
+    match strings
+        .iter()
+        .find(|string| string.len() > MAX_TXT_STRING_LENGTH)
+    {
+        Some(string) => Err(format!(
+            "TXT-record string of {} characters is longer than {}, split it into several quoted strings",
+            string.len(),
+            MAX_TXT_STRING_LENGTH
+        )),
+        None => Ok(strings),
+    }
+}
+
+fn parse_quoted_strings(data: &str) -> Result<Vec<String>, String> {
+    let mut strings = Vec::new();
+    let mut chars = data.trim().chars();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c != '"' {
+            return Err(format!(
+                "Unexpected character '{}' outside of quoted string",
+                c
+            ));
+        }
+        let mut string = String::new();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some(escaped) => string.push(escaped),
+                    None => return Err("Unterminated escape in TXT-record".to_string()),
+                },
+                Some(c) => string.push(c),
+                None => return Err("Unterminated quoted string in TXT-record".to_string()),
+            }
+        }
+        strings.push(string);
+    }
+    Ok(strings)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::dns::{
+        CNAMERecordData, DnsRecordData, MXRecordData, SRVRecordData, TXTRecordData, Ttl,
+    };
+
+    use super::{txt_strings, RecordField};
+
+    fn cname(host: &str, data: &str) -> DnsRecordData {
+        DnsRecordData::CNAME(CNAMERecordData {
+            host: host.to_string(),
+            ttl: Ttl::HOUR,
+            data: data.to_string(),
+        })
+    }
+
+    fn srv(host: &str, data: &str) -> DnsRecordData {
+        DnsRecordData::SRV(SRVRecordData {
+            host: host.to_string(),
+            ttl: Ttl::HOUR,
+            data: data.to_string(),
+            priority: 10,
+            weight: 5,
+            port: 5060,
+        })
+    }
+
+    fn txt(data: &str) -> DnsRecordData {
+        DnsRecordData::TXT(TXTRecordData {
+            host: "@".to_string(),
+            ttl: Ttl::HOUR,
+            data: data.to_string(),
+        })
+    }
+
+    fn fields(record: DnsRecordData) -> Vec<RecordField> {
+        record
+            .validate()
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect()
+    }
+
+    #[test]
+    fn valid_hosts_are_accepted() {
+        for host in ["@", "*", "*.dev", "www", "a.b-c.d", "_dmarc", "blåbær"] {
+            assert!(cname(host, "example.com").validate().is_empty(), "{}", host);
+        }
+    }
+
+    #[test]
+    fn invalid_hosts_are_rejected() {
+        let long_label = "a".repeat(64);
+        for host in ["", "-www", "www-", "a..b", "w w", "dev.*", &long_label] {
+            assert_eq!(
+                fields(cname(host, "example.com")),
+                vec![RecordField::Host],
+                "{}",
+                host
+            );
+        }
+    }
+
+    #[test]
+    fn targets_must_be_hostnames() {
+        assert!(cname("www", "example.com.").validate().is_empty());
+        assert_eq!(fields(cname("www", "@")), vec![RecordField::Data]);
+        assert_eq!(fields(cname("www", ".")), vec![RecordField::Data]);
+        assert_eq!(
+            fields(cname("www", "https://example.com")),
+            vec![RecordField::Data]
+        );
+
+        let null_mx = DnsRecordData::MX(MXRecordData {
+            host: "@".to_string(),
+            ttl: Ttl::HOUR,
+            data: ".".to_string(),
+            priority: 0,
+        });
+        assert!(null_mx.validate().is_empty());
+    }
+
+    #[test]
+    fn srv_host_must_name_service_and_protocol() {
+        assert!(srv("_sip._tcp", "sip.example.com").validate().is_empty());
+        assert!(srv("_sip._tcp.eu", "sip.example.com").validate().is_empty());
+        assert_eq!(
+            fields(srv("sip._tcp", "sip.example.com")),
+            vec![RecordField::Host]
+        );
+        assert_eq!(
+            fields(srv("_sip", "sip.example.com")),
+            vec![RecordField::Host]
+        );
+        assert_eq!(
+            fields(srv("www", "not a host")),
+            vec![RecordField::Host, RecordField::Data]
+        );
+    }
+
+    #[test]
+    fn deserialized_ttl_is_checked() {
+        for ttl in [0, 30, 90, 3599, 604_860] {
+            let record: DnsRecordData = serde_json::from_value(serde_json::json!({
+                "type": "CNAME",
+                "host": "www",
+                "ttl": ttl,
+                "data": "example.com"
+            }))
+            .unwrap();
+            assert_eq!(fields(record), vec![RecordField::Ttl], "{}", ttl);
+        }
+    }
+
+    #[test]
+    fn txt_length_and_quoting_is_checked() {
+        let long = "a".repeat(256);
+        assert!(txt("v=spf1 -all").validate().is_empty());
+        assert!(txt(&format!("\"{}\" \"{}\"", &long[1..], &long[1..]))
+            .validate()
+            .is_empty());
+        for data in [
+            "",
+            long.as_str(),
+            "say \"hello\"",
+            "\"unterminated",
+            "\"a\" b",
+        ] {
+            assert_eq!(fields(txt(data)), vec![RecordField::Data], "{}", data);
+        }
+    }
+
+    #[test]
+    fn txt_strings_are_unescaped() {
+        assert_eq!(
+            txt_strings("\"say \\\"hi\\\"\" \"two\"").unwrap(),
+            vec!["say \"hi\"".to_string(), "two".to_string()]
+        );
+        assert_eq!(txt_strings("plain text").unwrap(), vec!["plain text"]);
+    }
+}
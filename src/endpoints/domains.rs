@@ -3,7 +3,7 @@ use std::fmt::Display;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use crate::{client::DomeneshopClient, errors::DomeneshopError};
+use crate::{client::DomeneshopClient, errors::DomeneshopError, http::HttpClient};
 
 /// Id of a domain
 pub type DomainId = i32;
@@ -100,11 +100,25 @@ pub struct Domain {
 }
 
 /// Operations concerning domains
-impl DomeneshopClient {
+impl<H: HttpClient> DomeneshopClient<H> {
     /// Gets a single domain by id
     ///
     /// Domeneshop returns unauthorized when requesting a non-owned id. Cannot differentiate between credentials-error and this.
     /// This should really return `Result<Option<Domain>, DomeneShopError>`
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, id),
+            fields(
+                domain_id = id,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn get_domain(&self, id: DomainId) -> Result<Domain, DomeneshopError> {
         let url = self.create_url(format!("/domains/{}", id))?;
 
@@ -112,6 +126,14 @@ impl DomeneshopClient {
     }
 
     /// Lists all domains for the current user
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn list_domains(&self) -> Result<Vec<Domain>, DomeneshopError> {
         let url = self.create_url("/domains")?;
 
@@ -120,6 +142,20 @@ impl DomeneshopClient {
 
     /// Lists all domain for the current user.
     /// Only returns domains containing the text in `filter`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, filter),
+            fields(
+                filter = filter.as_ref(),
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn list_domains_with_filter(
         &self,
         filter: impl AsRef<str>,
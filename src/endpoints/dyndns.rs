@@ -2,15 +2,23 @@ use std::net::IpAddr;
 
 use http_types::{Method, Request, StatusCode};
 
-use crate::{client::DomeneshopClient, errors::DomeneshopError};
+use crate::{client::DomeneshopClient, errors::DomeneshopError, http::HttpClient};
 
 /// Operations concerning dynamic DNS
-impl DomeneshopClient {
+impl<H: HttpClient> DomeneshopClient<H> {
     /// Update DNS using the "IP update protocol".
     /// A DNS record for the given hostname will be created if it does not exist, or updated if it does.
     /// The record type (A or AAAA will automatically be detected).
     ///
     /// If `ip` is not provided, the IP of the client making the API request will be used.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, hostname),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn update_dyndns<S>(
         &self,
         hostname: S,
@@ -30,10 +38,7 @@ impl DomeneshopClient {
 
         match response.status() {
             StatusCode::NoContent => Ok(()),
-            _ => Err(DomeneshopError::new(format!(
-                "Encountered unexpected response status {}",
-                response.status()
-            ))),
+            _ => Err(DomeneshopError::unexpected_status(response.status())),
         }
     }
 }
@@ -5,6 +5,7 @@ use url::Url;
 use crate::{
     client::{handle_response_error, set_body, DomeneshopClient},
     errors::DomeneshopError,
+    http::HttpClient,
 };
 
 use super::domains::DomainId;
@@ -22,10 +23,24 @@ pub struct HttpForward {
 }
 
 /// Operations concerning HTTP Forwards
-impl DomeneshopClient {
+impl<H: HttpClient> DomeneshopClient<H> {
     /// Attempts to get an existing forward by host.
     /// Example: `www`
     /// Subdomain of the forward, `@` for the root domain
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, id),
+            fields(
+                domain_id = id,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn get_forward(
         &self,
         id: DomainId,
@@ -47,6 +62,20 @@ impl DomeneshopClient {
     }
 
     /// List all forwards for the specified domain.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, id),
+            fields(
+                domain_id = id,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn list_forwards(&self, id: DomainId) -> Result<Vec<HttpForward>, DomeneshopError> {
         let url = self.create_url(format!("/domains/{}/forwards", id))?;
 
@@ -55,6 +84,21 @@ impl DomeneshopClient {
 
     /// Create a forwarding for the specified domain, to a given URL.
     /// The forward must not collide with any existing forwarding or DNS record of types `A`, `AAAA`, `ANAME` or `CNAME`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, id, forward),
+            fields(
+                domain_id = id,
+                host = %forward.host,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn add_forward(
         &self,
         id: DomainId,
@@ -68,15 +112,27 @@ impl DomeneshopClient {
         let response = self.send(request).await?;
         match response.status() {
             StatusCode::Created => Ok(()),
-            _ => Err(DomeneshopError::new(format!(
-                "Encountered unexpected response status {}",
-                response.status()
-            ))),
+            _ => Err(DomeneshopError::unexpected_status(response.status())),
         }
     }
 
     /// Update a forwarding for the specified domain, to a given URL.
     /// The `host` field must not be changed. In that case, delete the existing forwarding and recreate it for the new host/subdomain.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, id, forward),
+            fields(
+                domain_id = id,
+                host = %forward.host,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn update_forward(
         &self,
         id: DomainId,
@@ -90,16 +146,21 @@ impl DomeneshopClient {
         let response = self.send(request).await?;
         match response.status() {
             StatusCode::Ok => Ok(()),
-            _ => Err(DomeneshopError::new(format!(
-                "Encountered unexpected response status {}",
-                response.status()
-            ))),
+            _ => Err(DomeneshopError::unexpected_status(response.status())),
         }
     }
 
     /// Deletes forward by host
     /// Example `www`.
     /// Subdomain for the forward, `@` for the root domain
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn delete_forward(
         &self,
         domain_id: DomainId,
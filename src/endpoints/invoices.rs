@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     client::{handle_response_error, DomeneshopClient},
     errors::DomeneshopError,
+    http::HttpClient,
 };
 
 /// Id of an invoice
@@ -109,8 +110,22 @@ pub struct Invoice {
 }
 
 /// Operations concerning invoices
-impl DomeneshopClient {
+impl<H: HttpClient> DomeneshopClient<H> {
     /// Get invoice by invoice number
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self, id),
+            fields(
+                invoice_id = id,
+                http.method,
+                url.path,
+                http.status_code,
+                latency_ms
+            ),
+            err
+        )
+    )]
     pub async fn get_invoice(&self, id: InvoiceId) -> Result<Option<Invoice>, DomeneshopError> {
         let url = self.create_url(format!("/invoices/{}", id))?;
 
@@ -128,6 +143,14 @@ impl DomeneshopClient {
     }
 
     /// List invoices for your account. Only invoices from the past 3 years are returned.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn list_invoices(&self) -> Result<Vec<Invoice>, DomeneshopError> {
         let url = self.create_url("/invoices")?;
 
@@ -135,6 +158,14 @@ impl DomeneshopClient {
     }
 
     /// Lists all the invoices for your account, filtered on a specific status
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(http.method, url.path, http.status_code, latency_ms),
+            err
+        )
+    )]
     pub async fn list_invoices_with_status(
         &self,
         status: InvoiceStatus,
@@ -1,14 +1,57 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, sync::Arc};
 
+use http_types::StatusCode;
 use serde::Deserialize;
 
+use crate::endpoints::dns::RecordViolation;
+
+/// The kind of error that occurred.
+///
+/// Use [`DomeneshopError::kind`] to inspect what went wrong instead of matching on the error message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DomeneshopErrorKind {
+    /// The request could not be sent or the response could not be received
+    Transport,
+    /// The url for the request could not be built
+    Url,
+    /// The response body could not be deserialized
+    Deserialization,
+    /// The API responded with an error
+    Api {
+        /// HTTP status of the response
+        status: StatusCode,
+        /// Error code returned by the API
+        code: String,
+        /// Help text returned by the API
+        help: String,
+    },
+    /// The API responded with a status the client did not expect
+    UnexpectedStatus(StatusCode),
+    /// The client was configured with invalid settings
+    Configuration,
+    /// The DNS record was not sent because it breaks the rules of the API.
+    /// Contains every violation found by [`DnsRecordData::validate`](crate::endpoints::dns::DnsRecordData::validate).
+    InvalidRecord(Vec<RecordViolation>),
+    /// Any other error
+    Other,
+}
+
 /// The error returned when operations fail against the API
 #[derive(Debug, Clone)]
 pub struct DomeneshopError {
+    kind: DomeneshopErrorKind,
     message: String,
+    source: Option<Arc<dyn Error + Send + Sync>>,
 }
 
-impl Error for DomeneshopError {}
+impl Error for DomeneshopError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
 
 impl Display for DomeneshopError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -17,18 +60,58 @@ impl Display for DomeneshopError {
 }
 
 impl DomeneshopError {
-    /// Creates a new `DomeneshopError`
+    /// Creates a new `DomeneshopError` of kind [`Other`](DomeneshopErrorKind::Other)
     pub fn new(message: impl Into<String>) -> DomeneshopError {
         DomeneshopError {
+            kind: DomeneshopErrorKind::Other,
             message: message.into(),
+            source: None,
         }
     }
 
-    pub(crate) fn from(text: impl Display) -> Self {
-        Self {
-            message: text.to_string(),
+    /// Creates a new `DomeneshopError` of kind [`Transport`](DomeneshopErrorKind::Transport).
+    /// Intended for implementations of [`HttpClient`](crate::http::HttpClient).
+    pub fn transport(
+        message: impl Into<String>,
+        source: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> DomeneshopError {
+        DomeneshopError::new(message)
+            .with_kind(DomeneshopErrorKind::Transport)
+            .with_source(source)
+    }
+
+    /// The kind of error
+    pub fn kind(&self) -> &DomeneshopErrorKind {
+        &self.kind
+    }
+
+    /// The HTTP status of the response that caused the error, if any
+    pub fn status(&self) -> Option<StatusCode> {
+        match self.kind {
+            DomeneshopErrorKind::Api { status, .. } => Some(status),
+            DomeneshopErrorKind::UnexpectedStatus(status) => Some(status),
+            _ => None,
         }
     }
+
+    pub(crate) fn with_kind(mut self, kind: DomeneshopErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub(crate) fn with_source(mut self, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        self.source = Some(Arc::from(source.into()));
+        self
+    }
+
+    pub(crate) fn configuration(message: impl Into<String>) -> Self {
+        DomeneshopError::new(message).with_kind(DomeneshopErrorKind::Configuration)
+    }
+
+    pub(crate) fn unexpected_status(status: StatusCode) -> Self {
+        DomeneshopError::new(format!("Encountered unexpected response status {}", status))
+            .with_kind(DomeneshopErrorKind::UnexpectedStatus(status))
+    }
 }
 
 /// Error returned from the Domeneshop Api
@@ -38,21 +121,71 @@ pub(crate) struct DomeneshopApiError {
     code: String,
 }
 
-impl Into<DomeneshopError> for DomeneshopApiError {
-    fn into(self) -> DomeneshopError {
-        DomeneshopError {
-            message: format!("{}: {}", self.code, self.help),
-        }
+impl DomeneshopApiError {
+    pub(crate) fn into_error(self, status: StatusCode) -> DomeneshopError {
+        DomeneshopError::new(format!("{}: {}", self.code, self.help)).with_kind(
+            DomeneshopErrorKind::Api {
+                status,
+                code: self.code,
+                help: self.help,
+            },
+        )
     }
 }
 
-pub(crate) fn to_domain_error(err: impl Display) -> DomeneshopError {
-    DomeneshopError::from(err)
+pub(crate) fn to_url_error(err: url::ParseError) -> DomeneshopError {
+    DomeneshopError::new(err.to_string())
+        .with_kind(DomeneshopErrorKind::Url)
+        .with_source(err)
+}
+
+pub(crate) fn to_deserialization_error(err: http_types::Error) -> DomeneshopError {
+    DomeneshopError::new(err.to_string())
+        .with_kind(DomeneshopErrorKind::Deserialization)
+        .with_source(err.into_inner())
 }
 
-pub(crate) fn to_domain_error_with_context(
+pub(crate) fn to_deserialization_error_with_context(
     context: impl Display,
-    err: impl Display,
+    err: http_types::Error,
 ) -> DomeneshopError {
-    DomeneshopError::from(format!("{}: {}", context, err))
+    DomeneshopError::new(format!("{}: {}", context, err))
+        .with_kind(DomeneshopErrorKind::Deserialization)
+        .with_source(err.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use http_types::StatusCode;
+
+    use super::{to_url_error, DomeneshopApiError, DomeneshopErrorKind};
+
+    #[test]
+    fn api_error_keeps_status_code_and_help() {
+        let error = DomeneshopApiError {
+            code: "dns:invalid".to_string(),
+            help: "Invalid record".to_string(),
+        }
+        .into_error(StatusCode::BadRequest);
+
+        assert_eq!(error.to_string(), "dns:invalid: Invalid record");
+        assert_eq!(error.status(), Some(StatusCode::BadRequest));
+        match error.kind() {
+            DomeneshopErrorKind::Api { code, help, .. } => {
+                assert_eq!(code, "dns:invalid");
+                assert_eq!(help, "Invalid record");
+            }
+            kind => panic!("Wrong error kind {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn url_error_keeps_source() {
+        let error = to_url_error(url::Url::parse("not a url").unwrap_err());
+
+        assert_eq!(error.kind(), &DomeneshopErrorKind::Url);
+        assert!(error.source().is_some());
+    }
 }
@@ -1,23 +1,24 @@
-use async_trait::async_trait;
+use std::{future::Future, pin::Pin, sync::Arc};
+
 use http_types::{Request, Response};
 
 use crate::errors::DomeneshopError;
 
-#[async_trait]
 /// A trait used to abstract the transport mechanism for sending requests.
 /// This is mostly added to make testing easier.
 ///
 /// If one wants to use another crate than `reqwest`, a separate implementation of this trait can be supplied when creating the [`DomeneshopClient`](./client/struct.DomeneshopClient.html).
+/// The client is generic over the implementation, so calls to it are not boxed.
+/// Use [`BoxedHttpClient`] if the type of the implementation must be erased.
 ///
 /// # Example
-/// To implement this trait, you need the [`async_trait`](https://docs.rs/async-trait/latest/async_trait/)-crate.
+/// The trait can be implemented using `async fn`, as long as the returned future is `Send`.
 /// ```
-///use async_trait::async_trait;
 ///use http_types::{Request, Response};
 ///use domeneshop_client::{errors::DomeneshopError, http::HttpClient};
 ///
 ///struct MyClient;
-///#[async_trait]
+///
 ///impl HttpClient for MyClient
 ///{
 ///    async fn execute_request(&self, request: Request) -> Result<Response, DomeneshopError> {
@@ -26,8 +27,90 @@ use crate::errors::DomeneshopError;
 ///}
 ///
 /// ```
-pub trait HttpClient {
+pub trait HttpClient: Send + Sync {
     /// Executes a request
     /// This defaults to a provided `reqwest`-implementation, but custom implementations can be created
-    async fn execute_request(&self, request: Request) -> Result<Response, DomeneshopError>;
+    fn execute_request(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Response, DomeneshopError>> + Send;
+}
+
+impl<T: HttpClient> HttpClient for Box<T> {
+    fn execute_request(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Response, DomeneshopError>> + Send {
+        (**self).execute_request(request)
+    }
+}
+
+impl<T: HttpClient> HttpClient for Arc<T> {
+    fn execute_request(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Response, DomeneshopError>> + Send {
+        (**self).execute_request(request)
+    }
+}
+
+/// A boxed future returned by [`DynHttpClient`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// An object safe version of [`HttpClient`], implemented for every [`HttpClient`].
+///
+/// Used through [`BoxedHttpClient`], which boxes the future of every request.
+pub trait DynHttpClient: Send + Sync {
+    /// Executes a request, returning a boxed future
+    fn execute_request_boxed(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'_, Result<Response, DomeneshopError>>;
+}
+
+impl<T: HttpClient> DynHttpClient for T {
+    fn execute_request_boxed(
+        &self,
+        request: Request,
+    ) -> BoxFuture<'_, Result<Response, DomeneshopError>> {
+        Box::pin(self.execute_request(request))
+    }
+}
+
+/// A type erased [`HttpClient`], for when the type of the implementation cannot be named or is chosen at runtime.
+///
+/// Every request is dispatched dynamically, and its future is boxed.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::client::{DomeneshopClient, DomeneshopClientConfiguration};
+/// use domeneshop_client::credentials::Credentials;
+/// use domeneshop_client::http::BoxedHttpClient;
+///
+/// # fn main() -> Result<(), domeneshop_client::errors::DomeneshopError> {
+/// let http_client = BoxedHttpClient::new(reqwest::Client::new());
+/// let client: DomeneshopClient<BoxedHttpClient> = DomeneshopClient::with_http_client(
+///     Credentials::new("token", "secret"),
+///     http_client,
+///     DomeneshopClientConfiguration::default(),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BoxedHttpClient(Box<dyn DynHttpClient>);
+
+impl BoxedHttpClient {
+    /// Erases the type of the client
+    pub fn new(client: impl HttpClient + 'static) -> Self {
+        BoxedHttpClient(Box::new(client))
+    }
+}
+
+impl HttpClient for BoxedHttpClient {
+    fn execute_request(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Response, DomeneshopError>> + Send {
+        self.0.execute_request_boxed(request)
+    }
 }
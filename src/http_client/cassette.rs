@@ -0,0 +1,421 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use base64::{engine::general_purpose, Engine};
+use http_types::{
+    headers::{HeaderName, HeaderValues},
+    Request, Response, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::DomeneshopError, http::HttpClient};
+
+/// Value stored in place of the `Authorization`-header in cassettes
+pub const SCRUBBED: &str = "[SCRUBBED]";
+
+/// A recorded session of request/response pairs, stored as JSON
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cassette {
+    /// The recorded interactions, in the order they were sent
+    pub interactions: Vec<Interaction>,
+}
+
+/// A single request and the response it received
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Interaction {
+    /// The request sent
+    pub request: RecordedRequest,
+    /// The response received
+    pub response: RecordedResponse,
+}
+
+/// A recorded request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// HTTP method, e.g. `GET`
+    pub method: String,
+    /// Absolute url, including the query
+    pub url: String,
+    /// Request headers. The `Authorization`-header is scrubbed.
+    pub headers: BTreeMap<String, Vec<String>>,
+    /// Request body. Empty if the request had no body.
+    pub body: String,
+    /// Whether `body` is base64-encoded, which is the case when the body is not valid UTF-8
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub body_base64: bool,
+}
+
+/// A recorded response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers
+    pub headers: BTreeMap<String, Vec<String>>,
+    /// Response body. Empty if the response had no body.
+    pub body: String,
+    /// Whether `body` is base64-encoded, which is the case when the body is not valid UTF-8
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub body_base64: bool,
+}
+
+impl RecordedRequest {
+    /// The body as it was sent, decoding it if it is base64-encoded
+    pub fn body_bytes(&self) -> Result<Vec<u8>, DomeneshopError> {
+        decode_body(&self.body, self.body_base64)
+    }
+}
+
+impl RecordedResponse {
+    /// The body as it was received, decoding it if it is base64-encoded
+    pub fn body_bytes(&self) -> Result<Vec<u8>, DomeneshopError> {
+        decode_body(&self.body, self.body_base64)
+    }
+}
+
+impl Cassette {
+    /// Reads a cassette from a file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DomeneshopError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|err| {
+            DomeneshopError::new(format!(
+                "Could not read cassette {}: {}",
+                path.display(),
+                err
+            ))
+            .with_source(err)
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            DomeneshopError::new(format!("Invalid cassette {}: {}", path.display(), err))
+                .with_source(err)
+        })
+    }
+
+    /// Writes the cassette to a file, replacing any existing file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DomeneshopError> {
+        let path = path.as_ref();
+        let content = serde_json::to_string_pretty(self).expect("cassette is serializable");
+        fs::write(path, content).map_err(|err| {
+            DomeneshopError::new(format!(
+                "Could not write cassette {}: {}",
+                path.display(),
+                err
+            ))
+            .with_source(err)
+        })
+    }
+}
+
+/// An [`HttpClient`] wrapping another client, recording every request and response to a cassette file.
+///
+/// The cassette is written after every interaction, and can be served back using [`ReplayClient`].
+/// The `Authorization`-header is never written to the cassette.
+///
+/// # Example
+/// ```no_run
+/// use domeneshop_client::client::{DomeneshopClient, DomeneshopClientConfiguration};
+/// use domeneshop_client::credentials::Credentials;
+/// use domeneshop_client::http_client::cassette::RecordingClient;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let recorder = RecordingClient::new(reqwest::Client::new(), "tests/cassettes/list_domains.json");
+/// let client = DomeneshopClient::with_http_client(
+///     Credentials::new("token", "secret"),
+///     recorder,
+///     DomeneshopClientConfiguration::default(),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RecordingClient<C> {
+    inner: C,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl<C> RecordingClient<C>
+where
+    C: HttpClient,
+{
+    /// Creates a new recording client writing to the cassette at `path`. An existing cassette is replaced.
+    pub fn new(inner: C, path: impl Into<PathBuf>) -> Self {
+        RecordingClient {
+            inner,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Returns a copy of the interactions recorded so far
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+}
+
+impl<C> HttpClient for RecordingClient<C>
+where
+    C: HttpClient + Send + Sync,
+{
+    async fn execute_request(&self, mut request: Request) -> Result<Response, DomeneshopError> {
+        let request_body = take_body(request.take_body()).await?;
+        let (body, body_base64) = encode_body(&request_body);
+        let recorded_request = RecordedRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers: scrub(collect_headers(request.iter())),
+            body,
+            body_base64,
+        };
+        if !request_body.is_empty() {
+            request.set_body(request_body);
+        }
+
+        let mut response = self.inner.execute_request(request).await?;
+        let response_body = take_body(response.take_body()).await?;
+        let (body, body_base64) = encode_body(&response_body);
+        let recorded_response = RecordedResponse {
+            status: response.status().into(),
+            headers: collect_headers(response.iter()),
+            body,
+            body_base64,
+        };
+        if !response_body.is_empty() {
+            response.set_body(response_body);
+        }
+
+        let cassette = {
+            let mut cassette = self.cassette.lock().unwrap();
+            cassette.interactions.push(Interaction {
+                request: recorded_request,
+                response: recorded_response,
+            });
+            cassette.clone()
+        };
+        cassette.save(&self.path)?;
+
+        Ok(response)
+    }
+}
+
+/// An [`HttpClient`] serving the responses of a cassette recorded with [`RecordingClient`].
+///
+/// Requests are matched on method, url and body. Every interaction is served once, in the order they were recorded.
+///
+/// The client fails loudly:
+/// - A request not matching the next interaction panics, listing the interactions that are left.
+///   This includes requests matching a later interaction, i.e. requests sent in another order than they were recorded.
+/// - Dropping the client while interactions are left unused panics. Use [`assert_all_used`](ReplayClient::assert_all_used) to check explicitly.
+///
+/// # Example
+/// ```no_run
+/// use domeneshop_client::client::{DomeneshopClient, DomeneshopClientConfiguration};
+/// use domeneshop_client::credentials::Credentials;
+/// use domeneshop_client::http_client::cassette::ReplayClient;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let replay = ReplayClient::from_file("tests/cassettes/list_domains.json")?;
+/// let client = DomeneshopClient::with_http_client(
+///     Credentials::new("token", "secret"),
+///     replay,
+///     DomeneshopClientConfiguration::default(),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplayClient {
+    interactions: Vec<Interaction>,
+    /// Index of the next interaction to serve
+    next: Mutex<usize>,
+}
+
+impl ReplayClient {
+    /// Creates a new replay client serving the interactions of the cassette
+    pub fn new(cassette: Cassette) -> Self {
+        ReplayClient {
+            interactions: cassette.interactions,
+            next: Mutex::new(0),
+        }
+    }
+
+    /// Creates a new replay client serving the cassette at `path`
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, DomeneshopError> {
+        Ok(ReplayClient::new(Cassette::load(path)?))
+    }
+
+    /// Returns the interactions that have not been served yet
+    pub fn unused_interactions(&self) -> Vec<Interaction> {
+        let next = *self.next.lock().unwrap();
+        self.interactions[next..].to_vec()
+    }
+
+    /// Panics if any interaction has not been served
+    pub fn assert_all_used(&self) {
+        let unused = self.unused_interactions();
+        if !unused.is_empty() {
+            panic!(
+                "{} interaction(s) in the cassette were never requested:\n{}",
+                unused.len(),
+                describe(&unused)
+            );
+        }
+    }
+}
+
+impl Drop for ReplayClient {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.assert_all_used();
+        }
+    }
+}
+
+impl HttpClient for ReplayClient {
+    async fn execute_request(&self, mut request: Request) -> Result<Response, DomeneshopError> {
+        let body = take_body(request.take_body()).await?;
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+
+        let recorded = {
+            let mut next = self.next.lock().unwrap();
+            let matches = |interaction: &Interaction| {
+                interaction.request.method == method
+                    && same_url(&interaction.request.url, &url)
+                    && same_body(&interaction.request, &body)
+            };
+            let unused = &self.interactions[*next..];
+            match unused.first() {
+                Some(interaction) if matches(interaction) => {
+                    *next += 1;
+                    interaction.response.clone()
+                }
+                _ if unused.iter().any(matches) => {
+                    drop(next);
+                    panic!(
+                        "{} {} (body: {}) was requested out of order. Unused interactions, in the order they were recorded:\n{}",
+                        method,
+                        url,
+                        describe_body(&body),
+                        describe(unused)
+                    );
+                }
+                _ => {
+                    drop(next);
+                    panic!(
+                        "No interaction in the cassette matches {} {} (body: {}). Unused interactions:\n{}",
+                        method,
+                        url,
+                        describe_body(&body),
+                        describe(unused)
+                    );
+                }
+            }
+        };
+
+        let status = StatusCode::try_from(recorded.status).map_err(|err| {
+            DomeneshopError::new(format!("Invalid status in cassette: {}", recorded.status))
+                .with_source(err.into_inner())
+        })?;
+        let mut response = Response::new(status);
+        for (name, values) in &recorded.headers {
+            for value in values {
+                response.append_header(name.as_str(), value.as_str());
+            }
+        }
+        let body = recorded.body_bytes()?;
+        if !body.is_empty() {
+            response.set_body(body);
+        }
+        Ok(response)
+    }
+}
+
+pub(crate) async fn take_body(body: http_types::Body) -> Result<Vec<u8>, DomeneshopError> {
+    body.into_bytes().await.map_err(|err| {
+        DomeneshopError::new(format!("Could not read body: {}", err)).with_source(err.into_inner())
+    })
+}
+
+pub(crate) fn collect_headers<'a>(
+    headers: impl Iterator<Item = (&'a HeaderName, &'a HeaderValues)>,
+) -> BTreeMap<String, Vec<String>> {
+    headers
+        .map(|(name, values)| {
+            (
+                name.as_str().to_lowercase(),
+                values.iter().map(|value| value.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+fn scrub(mut headers: BTreeMap<String, Vec<String>>) -> BTreeMap<String, Vec<String>> {
+    if let Some(values) = headers.get_mut("authorization") {
+        *values = vec![SCRUBBED.to_string()];
+    }
+    headers
+}
+
+fn same_url(recorded: &str, actual: &str) -> bool {
+    match (Url::parse(recorded), Url::parse(actual)) {
+        (Ok(recorded), Ok(actual)) => recorded == actual,
+        _ => recorded == actual,
+    }
+}
+
+fn same_body(recorded: &RecordedRequest, actual: &[u8]) -> bool {
+    let Ok(recorded) = recorded.body_bytes() else {
+        return false;
+    };
+    match (
+        serde_json::from_slice::<serde_json::Value>(&recorded),
+        serde_json::from_slice::<serde_json::Value>(actual),
+    ) {
+        (Ok(recorded), Ok(actual)) => recorded == actual,
+        _ => recorded == actual,
+    }
+}
+
+/// Stores UTF-8 bodies as is, and other bodies base64-encoded so no bytes are lost
+pub(crate) fn encode_body(body: &[u8]) -> (String, bool) {
+    match std::str::from_utf8(body) {
+        Ok(body) => (body.to_string(), false),
+        Err(_) => (general_purpose::STANDARD.encode(body), true),
+    }
+}
+
+fn decode_body(body: &str, base64: bool) -> Result<Vec<u8>, DomeneshopError> {
+    if !base64 {
+        return Ok(body.as_bytes().to_vec());
+    }
+    general_purpose::STANDARD.decode(body).map_err(|err| {
+        DomeneshopError::new(format!("Invalid base64 body in cassette: {}", err)).with_source(err)
+    })
+}
+
+fn describe_body(body: &[u8]) -> String {
+    match std::str::from_utf8(body) {
+        Ok(body) => format!("{:?}", body),
+        Err(_) => format!("{} bytes, not UTF-8", body.len()),
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+fn describe(interactions: &[Interaction]) -> String {
+    interactions
+        .iter()
+        .map(|interaction| {
+            format!(
+                "  {} {} -> {}",
+                interaction.request.method, interaction.request.url, interaction.response.status
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
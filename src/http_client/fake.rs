@@ -0,0 +1,757 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::{IpAddr, Ipv4Addr},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use base64::{engine::general_purpose, Engine};
+use chrono::NaiveDate;
+use http_types::{Method, Request, Response, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    cache::api_segments,
+    credentials::Credentials,
+    endpoints::{
+        dns::{AAAARecordData, ARecordData, DnsId, DnsRecordData, DnsType, ExistingDnsRecord, Ttl},
+        domains::{Domain, DomainId, DomainServices, DomainStatus, WebhotelType},
+        forwards::HttpForward,
+        invoices::{Invoice, InvoiceId},
+    },
+    errors::DomeneshopError,
+    http::HttpClient,
+};
+
+/// TTL of records created through the dyndns-endpoint
+const DYNDNS_TTL: Ttl = Ttl::HOUR;
+
+/// The data kept by a [`FakeDomeneshop`].
+///
+/// Can be serialized to and from JSON, e.g. to seed the fake from a fixture file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct FakeState {
+    /// All domains of the account
+    pub domains: Vec<Domain>,
+    /// DNS records by domain
+    pub dns: BTreeMap<DomainId, Vec<ExistingDnsRecord>>,
+    /// HTTP forwards by domain
+    pub forwards: BTreeMap<DomainId, Vec<HttpForward>>,
+    /// All invoices of the account
+    pub invoices: Vec<Invoice>,
+}
+
+impl FakeState {
+    /// Reads the state from a JSON fixture file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DomeneshopError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|err| {
+            DomeneshopError::new(format!(
+                "Could not read fixture {}: {}",
+                path.display(),
+                err
+            ))
+            .with_source(err)
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            DomeneshopError::new(format!("Invalid fixture {}: {}", path.display(), err))
+                .with_source(err)
+        })
+    }
+}
+
+/// A stateful, in-memory fake of the Domeneshop API, implementing [`HttpClient`].
+///
+/// Changes made through the client are kept, so a record added with `add_dns_record` is returned by `list_dns_records`.
+/// The fake enforces the rules of the real API, and responds with the same status codes:
+/// - Unknown domains, records, forwards and invoices respond with `404 Not Found`.
+/// - Requests with other credentials than the ones given to [`with_credentials`](FakeDomeneshop::with_credentials) respond with `401 Unauthorized`.
+/// - DNS and forward endpoints respond with `403 Forbidden` for domains without DNS service.
+/// - TTLs must be a multiple of 60, and record data must be valid for the record type (`400 Bad Request`).
+/// - Records colliding with existing records or forwards respond with `409 Conflict`.
+/// - Adding a DNS record responds with `201 Created` and a `Location`-header, updating it with `204 No Content`.
+///
+/// The fake is cheap to clone, and all clones share the same state.
+/// Keep a clone to inspect the state after handing the fake to the client.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::client::{DomeneshopClient, DomeneshopClientConfiguration};
+/// use domeneshop_client::credentials::Credentials;
+/// use domeneshop_client::endpoints::dns::{DnsRecordData, TXTRecordData, Ttl};
+/// use domeneshop_client::http_client::fake::{self, FakeDomeneshop};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # tokio_test::block_on(async {
+/// let fake = FakeDomeneshop::new();
+/// fake.add_domain(fake::domain(1, "example.com"));
+///
+/// let client = DomeneshopClient::with_http_client(
+///     Credentials::new("token", "secret"),
+///     fake.clone(),
+///     DomeneshopClientConfiguration::default(),
+/// )?;
+///
+/// let record = DnsRecordData::TXT(TXTRecordData {
+///     host: "@".to_string(),
+///     ttl: Ttl::HOUR,
+///     data: "hello".to_string(),
+/// });
+/// client.add_dns_record(1, record).await?;
+///
+/// assert_eq!(client.list_dns_records(1).await?.len(), 1);
+/// assert_eq!(fake.dns_records(1).len(), 1);
+/// #     Ok(())
+/// # })
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FakeDomeneshop {
+    state: Arc<Mutex<FakeState>>,
+    credentials: Option<Credentials>,
+    client_ip: IpAddr,
+}
+
+impl Default for FakeDomeneshop {
+    fn default() -> Self {
+        FakeDomeneshop::from_state(FakeState::default())
+    }
+}
+
+/// Creates an active domain with DNS service, for seeding a [`FakeDomeneshop`]
+pub fn domain(id: DomainId, name: &str) -> Domain {
+    Domain {
+        id,
+        domain: name.to_string(),
+        expiry_date: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+        registered_date: NaiveDate::from_ymd_opt(2020, 1, 1),
+        renew: true,
+        registrant: "Fake Registrant".to_string(),
+        status: DomainStatus::Active,
+        nameservers: vec!["ns1.hyp.net".to_string(), "ns2.hyp.net".to_string()],
+        services: DomainServices {
+            registrar: true,
+            dns: true,
+            email: false,
+            webhotel: WebhotelType::None,
+        },
+    }
+}
+
+impl FakeDomeneshop {
+    /// Creates an empty fake accepting any credentials
+    pub fn new() -> Self {
+        FakeDomeneshop::default()
+    }
+
+    /// Creates a fake with the given state, accepting any credentials
+    pub fn from_state(state: FakeState) -> Self {
+        FakeDomeneshop {
+            state: Arc::new(Mutex::new(state)),
+            credentials: None,
+            client_ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+        }
+    }
+
+    /// Only accepts requests using the given credentials
+    pub fn with_credentials(mut self, token: impl Into<String>, secret: impl Into<String>) -> Self {
+        self.credentials = Some(Credentials::new(token, secret));
+        self
+    }
+
+    /// Sets the IP used by the dyndns-endpoint when no IP is given. Defaults to `127.0.0.1`.
+    pub fn with_client_ip(mut self, ip: IpAddr) -> Self {
+        self.client_ip = ip;
+        self
+    }
+
+    /// Adds a domain to the account
+    pub fn add_domain(&self, domain: Domain) {
+        self.state.lock().unwrap().domains.push(domain);
+    }
+
+    /// Adds a DNS record to a domain without validating it, returning the id of the record
+    pub fn add_dns_record(&self, domain_id: DomainId, data: DnsRecordData) -> DnsId {
+        let mut state = self.state.lock().unwrap();
+        let id = next_dns_id(&state);
+        state
+            .dns
+            .entry(domain_id)
+            .or_default()
+            .push(ExistingDnsRecord { id, data });
+        id
+    }
+
+    /// Adds a forward to a domain without validating it
+    pub fn add_forward(&self, domain_id: DomainId, forward: HttpForward) {
+        let mut state = self.state.lock().unwrap();
+        state.forwards.entry(domain_id).or_default().push(forward);
+    }
+
+    /// Adds an invoice to the account
+    pub fn add_invoice(&self, invoice: Invoice) {
+        self.state.lock().unwrap().invoices.push(invoice);
+    }
+
+    /// Returns a copy of the current state
+    pub fn state(&self) -> FakeState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Returns the DNS records of a domain
+    pub fn dns_records(&self, domain_id: DomainId) -> Vec<ExistingDnsRecord> {
+        let state = self.state.lock().unwrap();
+        state.dns.get(&domain_id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the forwards of a domain
+    pub fn forwards(&self, domain_id: DomainId) -> Vec<HttpForward> {
+        let state = self.state.lock().unwrap();
+        state.forwards.get(&domain_id).cloned().unwrap_or_default()
+    }
+
+    fn handle(
+        &self,
+        method: Method,
+        url: &Url,
+        authorization: Option<&str>,
+        body: &[u8],
+    ) -> Result<Response, FakeError> {
+        if !self.is_authorized(authorization) {
+            return Err(error(
+                StatusCode::Unauthorized,
+                "access:unauthorized",
+                "Invalid credentials",
+            ));
+        }
+
+        let segments = api_segments(url);
+        let mut state = self.state.lock().unwrap();
+        match (method, segments.as_slice()) {
+            (Method::Get, ["domains"]) => {
+                let filter = query(url, "domain");
+                let domains: Vec<_> = state
+                    .domains
+                    .iter()
+                    .filter(|domain| filter.as_ref().map_or(true, |f| domain.domain.contains(f)))
+                    .collect();
+                Ok(json(StatusCode::Ok, &domains))
+            }
+            (Method::Get, ["domains", id]) => {
+                let domain = find_domain(&state, id)?;
+                Ok(json(StatusCode::Ok, domain))
+            }
+            (Method::Get, ["domains", id, "dns"]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let host = query(url, "host");
+                let dns_type = query(url, "type");
+                let records: Vec<_> = records(&state, domain_id)
+                    .iter()
+                    .filter(|record| {
+                        host.as_ref()
+                            .map_or(true, |host| same_host(record.data.host(), host))
+                    })
+                    .filter(|record| {
+                        dns_type
+                            .as_ref()
+                            .map_or(true, |t| record.data.dns_type().to_string() == *t)
+                    })
+                    .collect();
+                Ok(json(StatusCode::Ok, &records))
+            }
+            (Method::Post, ["domains", id, "dns"]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let data = parse_record(body)?;
+                validate_record(&state, domain_id, &data, None)?;
+                let id = next_dns_id(&state);
+                state
+                    .dns
+                    .entry(domain_id)
+                    .or_default()
+                    .push(ExistingDnsRecord { id, data });
+
+                let mut response = json(StatusCode::Created, &serde_json::json!({ "id": id }));
+                response.insert_header("Location", location(url, &id.to_string()).as_str());
+                Ok(response)
+            }
+            (Method::Get, ["domains", id, "dns", dns_id]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let index = find_record(&state, domain_id, dns_id)?;
+                Ok(json(StatusCode::Ok, &records(&state, domain_id)[index]))
+            }
+            (Method::Put, ["domains", id, "dns", dns_id]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let index = find_record(&state, domain_id, dns_id)?;
+                let data = parse_record(body)?;
+                let record_id = records(&state, domain_id)[index].id;
+                if data.dns_type() != records(&state, domain_id)[index].data.dns_type() {
+                    return Err(error(
+                        StatusCode::BadRequest,
+                        "record:invalid_type",
+                        "The type of an existing record cannot be changed",
+                    ));
+                }
+                validate_record(&state, domain_id, &data, Some(record_id))?;
+                state.dns.get_mut(&domain_id).unwrap()[index].data = data;
+                Ok(Response::new(StatusCode::NoContent))
+            }
+            (Method::Delete, ["domains", id, "dns", dns_id]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let index = find_record(&state, domain_id, dns_id)?;
+                state.dns.get_mut(&domain_id).unwrap().remove(index);
+                Ok(Response::new(StatusCode::NoContent))
+            }
+            (Method::Get, ["domains", id, "forwards"]) => {
+                let domain_id = dns_domain(&state, id)?;
+                Ok(json(StatusCode::Ok, forwards(&state, domain_id)))
+            }
+            (Method::Post, ["domains", id, "forwards"]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let forward: HttpForward = parse_body(body)?;
+                validate_forward(&state, domain_id, &forward, None)?;
+                let mut response = json(StatusCode::Created, &forward);
+                response.insert_header("Location", location(url, &forward.host).as_str());
+                state.forwards.entry(domain_id).or_default().push(forward);
+                Ok(response)
+            }
+            (Method::Get, ["domains", id, "forwards", host]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let index = find_forward(&state, domain_id, host)?;
+                Ok(json(StatusCode::Ok, &forwards(&state, domain_id)[index]))
+            }
+            (Method::Put, ["domains", id, "forwards", host]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let index = find_forward(&state, domain_id, host)?;
+                let forward: HttpForward = parse_body(body)?;
+                validate_forward(&state, domain_id, &forward, Some(index))?;
+                let response = json(StatusCode::Ok, &forward);
+                state.forwards.get_mut(&domain_id).unwrap()[index] = forward;
+                Ok(response)
+            }
+            (Method::Delete, ["domains", id, "forwards", host]) => {
+                let domain_id = dns_domain(&state, id)?;
+                let index = find_forward(&state, domain_id, host)?;
+                state.forwards.get_mut(&domain_id).unwrap().remove(index);
+                Ok(Response::new(StatusCode::NoContent))
+            }
+            (Method::Get, ["invoices"]) => {
+                let status = query(url, "status");
+                let invoices: Vec<_> = state
+                    .invoices
+                    .iter()
+                    .filter(|invoice| {
+                        status.as_ref().map_or(true, |status| {
+                            serde_json::to_value(&invoice.status).unwrap() == status.as_str()
+                        })
+                    })
+                    .collect();
+                Ok(json(StatusCode::Ok, &invoices))
+            }
+            (Method::Get, ["invoices", id]) => {
+                let invoice = id
+                    .parse::<InvoiceId>()
+                    .ok()
+                    .and_then(|id| state.invoices.iter().find(|invoice| invoice.id == id))
+                    .ok_or_else(|| {
+                        error(
+                            StatusCode::NotFound,
+                            "invoice:not_found",
+                            "Invoice not found",
+                        )
+                    })?;
+                Ok(json(StatusCode::Ok, invoice))
+            }
+            (Method::Get, ["dyndns", "update"]) => {
+                self.update_dyndns(&mut state, url)?;
+                Ok(Response::new(StatusCode::NoContent))
+            }
+            _ => Err(error(
+                StatusCode::NotFound,
+                "request:not_found",
+                "No such endpoint",
+            )),
+        }
+    }
+
+    fn is_authorized(&self, authorization: Option<&str>) -> bool {
+        let credentials = match &self.credentials {
+            Some(credentials) => credentials,
+            None => return true,
+        };
+        let decoded = authorization
+            .and_then(|header| header.strip_prefix("Basic "))
+            .and_then(|encoded| {
+                general_purpose::STANDARD_NO_PAD
+                    .decode(encoded.trim_end_matches('='))
+                    .ok()
+            })
+            .and_then(|decoded| String::from_utf8(decoded).ok());
+        match decoded {
+            Some(decoded) => decoded == format!("{}:{}", credentials.token(), credentials.secret()),
+            None => false,
+        }
+    }
+
+    fn update_dyndns(&self, state: &mut FakeState, url: &Url) -> Result<(), FakeError> {
+        let hostname = query(url, "hostname").ok_or_else(|| {
+            error(
+                StatusCode::BadRequest,
+                "dyndns:missing_hostname",
+                "The hostname parameter is required",
+            )
+        })?;
+        let ip = match query(url, "myip") {
+            Some(ip) => ip.parse::<IpAddr>().map_err(|_| {
+                error(
+                    StatusCode::BadRequest,
+                    "dyndns:invalid_ip",
+                    "The myip parameter is not a valid IP address",
+                )
+            })?,
+            None => self.client_ip,
+        };
+
+        let hostname = hostname.trim_end_matches('.').to_lowercase();
+        let domain = state
+            .domains
+            .iter()
+            .filter(|domain| {
+                hostname == domain.domain || hostname.ends_with(&format!(".{}", domain.domain))
+            })
+            .max_by_key(|domain| domain.domain.len())
+            .ok_or_else(|| {
+                error(
+                    StatusCode::NotFound,
+                    "domain:not_found",
+                    "No domain found for the hostname",
+                )
+            })?;
+        let domain_id = domain.id;
+        let host = match hostname.strip_suffix(&format!(".{}", domain.domain)) {
+            Some(host) => host.to_string(),
+            None => "@".to_string(),
+        };
+        let data = match ip {
+            IpAddr::V4(ip) => DnsRecordData::A(ARecordData {
+                host: host.clone(),
+                ttl: DYNDNS_TTL,
+                data: ip,
+            }),
+            IpAddr::V6(ip) => DnsRecordData::AAAA(AAAARecordData {
+                host: host.clone(),
+                ttl: DYNDNS_TTL,
+                data: ip,
+            }),
+        };
+
+        let existing = records(state, domain_id).iter().position(|record| {
+            record.data.dns_type() == data.dns_type() && same_host(record.data.host(), &host)
+        });
+        match existing {
+            Some(index) => state.dns.get_mut(&domain_id).unwrap()[index].data = data,
+            None => {
+                let id = next_dns_id(state);
+                state
+                    .dns
+                    .entry(domain_id)
+                    .or_default()
+                    .push(ExistingDnsRecord { id, data });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl HttpClient for FakeDomeneshop {
+    async fn execute_request(&self, mut request: Request) -> Result<Response, DomeneshopError> {
+        let body = request.take_body().into_bytes().await.map_err(|err| {
+            DomeneshopError::new(format!("Could not read request body: {}", err))
+                .with_source(err.into_inner())
+        })?;
+        let authorization = request
+            .header("Authorization")
+            .map(|header| header.last().as_str().to_string());
+
+        // Error responses are returned as responses, just like the real API does
+        let response = self.handle(
+            request.method(),
+            request.url(),
+            authorization.as_deref(),
+            &body,
+        );
+        Ok(response.unwrap_or_else(FakeError::into_response))
+    }
+}
+
+fn query(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn location(url: &Url, id: &str) -> Url {
+    let mut location = url.clone();
+    location.set_query(None);
+    location
+        .path_segments_mut()
+        .expect("request url is absolute")
+        .pop_if_empty()
+        .push(id);
+    location
+}
+
+fn json<T: Serialize + ?Sized>(status: StatusCode, body: &T) -> Response {
+    let mut response = Response::new(status);
+    response.insert_header("Content-Type", "application/json");
+    response.set_body(serde_json::to_string(body).expect("state is serializable"));
+    response
+}
+
+/// An error response of the API
+struct FakeError {
+    status: StatusCode,
+    code: &'static str,
+    help: String,
+}
+
+impl FakeError {
+    fn into_response(self) -> Response {
+        let mut response = json(
+            self.status,
+            &serde_json::json!({
+                "code": self.code,
+                "help": self.help,
+            }),
+        );
+        if self.status == StatusCode::Unauthorized {
+            response.insert_header("WWW-Authenticate", "Basic realm=\"Domeneshop API\"");
+        }
+        response
+    }
+}
+
+fn error(status: StatusCode, code: &'static str, help: impl Into<String>) -> FakeError {
+    FakeError {
+        status,
+        code,
+        help: help.into(),
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, FakeError> {
+    serde_json::from_slice(body).map_err(|err| {
+        error(
+            StatusCode::BadRequest,
+            "request:invalid_body",
+            format!("Invalid request body: {}", err),
+        )
+    })
+}
+
+/// Parses a DNS record, responding with the same errors as the API for an invalid TTL or data
+fn parse_record(body: &[u8]) -> Result<DnsRecordData, FakeError> {
+    let value: serde_json::Value = parse_body(body)?;
+    let invalid = |code: &'static str, help: String| error(StatusCode::BadRequest, code, help);
+    if let Some(ttl) = value.get("ttl") {
+        Ttl::deserialize(ttl)
+            .map_err(|err| err.to_string())
+            .and_then(|ttl| Ttl::new(ttl.seconds()).map_err(|err| err.to_string()))
+            .map_err(|err| invalid("record:invalid_ttl", format!("Invalid TTL: {}", err)))?;
+    }
+    serde_json::from_value(value)
+        .map_err(|err| invalid("record:invalid_data", format!("Invalid record: {}", err)))
+}
+
+fn find_domain<'a>(state: &'a FakeState, id: &str) -> Result<&'a Domain, FakeError> {
+    id.parse::<DomainId>()
+        .ok()
+        .and_then(|id| state.domains.iter().find(|domain| domain.id == id))
+        .ok_or_else(|| error(StatusCode::NotFound, "domain:not_found", "Domain not found"))
+}
+
+/// Finds a domain with DNS service, returning its id
+fn dns_domain(state: &FakeState, id: &str) -> Result<DomainId, FakeError> {
+    let domain = find_domain(state, id)?;
+    if !domain.services.dns {
+        return Err(error(
+            StatusCode::Forbidden,
+            "domain:no_dns_service",
+            "The domain does not have DNS service",
+        ));
+    }
+    Ok(domain.id)
+}
+
+fn records(state: &FakeState, domain_id: DomainId) -> &[ExistingDnsRecord] {
+    state.dns.get(&domain_id).map_or(&[], Vec::as_slice)
+}
+
+fn forwards(state: &FakeState, domain_id: DomainId) -> &[HttpForward] {
+    state.forwards.get(&domain_id).map_or(&[], Vec::as_slice)
+}
+
+fn find_record(state: &FakeState, domain_id: DomainId, dns_id: &str) -> Result<usize, FakeError> {
+    dns_id
+        .parse::<DnsId>()
+        .ok()
+        .and_then(|dns_id| {
+            records(state, domain_id)
+                .iter()
+                .position(|record| record.id == dns_id)
+        })
+        .ok_or_else(|| {
+            error(
+                StatusCode::NotFound,
+                "record:not_found",
+                "DNS record not found",
+            )
+        })
+}
+
+fn find_forward(state: &FakeState, domain_id: DomainId, host: &str) -> Result<usize, FakeError> {
+    forwards(state, domain_id)
+        .iter()
+        .position(|forward| same_host(&forward.host, host))
+        .ok_or_else(|| {
+            error(
+                StatusCode::NotFound,
+                "forward:not_found",
+                "Forward not found",
+            )
+        })
+}
+
+fn next_dns_id(state: &FakeState) -> DnsId {
+    state
+        .dns
+        .values()
+        .flatten()
+        .map(|record| record.id)
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+fn same_host(a: &str, b: &str) -> bool {
+    normalize_host(a) == normalize_host(b)
+}
+
+fn normalize_host(host: &str) -> String {
+    match host.trim_end_matches('.') {
+        "" => "@".to_string(),
+        host => host.to_lowercase(),
+    }
+}
+
+/// Record types that are served by a forward, and therefore collide with it
+fn is_address_record(dns_type: DnsType) -> bool {
+    matches!(
+        dns_type,
+        DnsType::A | DnsType::AAAA | DnsType::ANAME | DnsType::CNAME
+    )
+}
+
+fn validate_record(
+    state: &FakeState,
+    domain_id: DomainId,
+    record: &DnsRecordData,
+    existing_id: Option<DnsId>,
+) -> Result<(), FakeError> {
+    let invalid = |code: &'static str, help: &str| Err(error(StatusCode::BadRequest, code, help));
+    let collision = |help: &str| Err(error(StatusCode::Conflict, "record:collision", help));
+
+    let valid_data = match record {
+        DnsRecordData::NS(record) => normalize_host(&record.host) != "@",
+        _ => true,
+    };
+    if !valid_data || record.data().is_empty() {
+        return invalid(
+            "record:invalid_data",
+            &format!("Invalid data for {}-record", record.dns_type()),
+        );
+    }
+
+    let host = record.host();
+    let others = records(state, domain_id)
+        .iter()
+        .filter(|other| Some(other.id) != existing_id)
+        .filter(|other| same_host(other.data.host(), host));
+    for other in others {
+        if other.data.dns_type() == record.dns_type() && other.data.data() == record.data() {
+            return collision("An identical record already exists");
+        }
+        if other.data.dns_type() == DnsType::CNAME || record.dns_type() == DnsType::CNAME {
+            return collision(
+                "A CNAME-record cannot be combined with other records on the same host",
+            );
+        }
+    }
+    if is_address_record(record.dns_type())
+        && forwards(state, domain_id)
+            .iter()
+            .any(|forward| same_host(&forward.host, host))
+    {
+        return collision("The host is used by a forward");
+    }
+    Ok(())
+}
+
+fn validate_forward(
+    state: &FakeState,
+    domain_id: DomainId,
+    forward: &HttpForward,
+    existing_index: Option<usize>,
+) -> Result<(), FakeError> {
+    let collides_with_forward =
+        forwards(state, domain_id)
+            .iter()
+            .enumerate()
+            .any(|(index, other)| {
+                Some(index) != existing_index && same_host(&other.host, &forward.host)
+            });
+    if collides_with_forward {
+        return Err(error(
+            StatusCode::Conflict,
+            "forward:collision",
+            "A forward for the host already exists",
+        ));
+    }
+
+    let collides_with_record = records(state, domain_id).iter().any(|record| {
+        is_address_record(record.data.dns_type()) && same_host(record.data.host(), &forward.host)
+    });
+    if collides_with_record {
+        return Err(error(
+            StatusCode::Conflict,
+            "forward:collision",
+            "The host has an A, AAAA or CNAME-record",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use http_types::Url;
+
+    use super::{location, same_host};
+
+    #[test]
+    fn location_appends_id() {
+        let url = Url::parse("https://api.domeneshop.no/v0/domains/1/dns?x=y").unwrap();
+
+        assert_eq!(
+            location(&url, "5").as_str(),
+            "https://api.domeneshop.no/v0/domains/1/dns/5"
+        );
+    }
+
+    #[test]
+    fn hosts_are_compared_case_insensitively() {
+        assert!(same_host("WWW", "www"));
+        assert!(same_host("", "@"));
+        assert!(!same_host("www", "@"));
+    }
+}
@@ -1,12 +1,24 @@
-use async_trait::async_trait;
-use http_types::{Request, Response};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use http_types::{Method, Request, Response, StatusCode, Url};
+
+use crate::{
+    cache::api_segments,
+    errors::DomeneshopError,
+    http::HttpClient,
+    http_client::cassette::{collect_headers, encode_body, take_body, RecordedRequest},
+};
 
-use crate::{errors::DomeneshopError, http::HttpClient};
 /// A mock client that can be used for testing code using this crate.
 ///
 /// # Example
 /// ```
 /// use domeneshop_client::client::{ DomeneshopClientConfiguration, DomeneshopClient };
+/// use domeneshop_client::credentials::Credentials;
 /// use domeneshop_client::http_client::mock::MockClient;
 /// use domeneshop_client::errors::DomeneshopError;
 /// use http_types::{ StatusCode, Response, Request };
@@ -24,13 +36,12 @@ use crate::{errors::DomeneshopError, http::HttpClient};
 ///     req_received: receive_request,
 /// };
 ///
-/// let client = DomeneshopClient::new(
-///     String::from("token"),
-///     String::from("secret"),
+/// let client = DomeneshopClient::with_http_client(
+///     Credentials::new("token", "secret"),
+///     mock,
 ///     DomeneshopClientConfiguration {
-///         user_agent: None,
 ///         base_url: Some("https://localhost".to_string()),
-///         underlying_client: Some(Box::new(mock)),
+///         ..Default::default()
 ///     }
 /// )?;
 /// let domains = client.list_domains_with_filter(".no").await?;
@@ -50,7 +61,6 @@ where
     pub req_received: fn(Request) -> F,
 }
 
-#[async_trait]
 impl<F> HttpClient for MockClient<F>
 where
     F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send,
@@ -59,3 +69,288 @@ where
         (self.req_received)(request).await
     }
 }
+
+/// The blocking equivalent of [`MockClient`], used for testing code using the [`blocking`](crate::blocking)-client.
+/// Only available when both the `mock` and `blocking`-features are enabled.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::blocking::{ DomeneshopClientConfiguration, DomeneshopClient };
+/// use domeneshop_client::http_client::mock::BlockingMockClient;
+/// use domeneshop_client::errors::DomeneshopError;
+/// use http_types::{ StatusCode, Response, Request };
+///
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// fn receive_request(req: Request) -> Result<Response, DomeneshopError> {
+///     let mut response = Response::new(StatusCode::Ok);
+///     response.set_body("[]");
+///     Ok(response)
+/// }
+///
+/// let mock = BlockingMockClient {
+///     req_received: receive_request,
+/// };
+///
+/// let client = DomeneshopClient::new(
+///     String::from("token"),
+///     String::from("secret"),
+///     DomeneshopClientConfiguration {
+///         base_url: Some("https://localhost".to_string()),
+///         underlying_client: Some(Box::new(mock)),
+///         ..Default::default()
+///     }
+/// )?;
+/// let domains = client.list_domains_with_filter(".no")?;
+///
+/// assert_eq!(domains.len(), 0);
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub struct BlockingMockClient {
+    /// Callback that is invoked when a request is received
+    pub req_received: fn(Request) -> Result<Response, DomeneshopError>,
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::BlockingHttpClient for BlockingMockClient {
+    fn execute_request(&self, request: Request) -> Result<Response, DomeneshopError> {
+        (self.req_received)(request)
+    }
+}
+
+type Handler = dyn Fn(Request) -> Pin<Box<dyn Future<Output = Result<Response, DomeneshopError>> + Send>>
+    + Send
+    + Sync;
+
+/// A mock client built from closures and expectations, for tests that need to capture state or assert on the calls made.
+///
+/// Requests are first matched against the registered expectations, in the order they were registered.
+/// Requests not matching an expectation are passed to the handler given to [`with_handler`](ClosureMockClient::with_handler),
+/// and panic if there is none.
+///
+/// Every request is recorded, and can be inspected with [`calls`](ClosureMockClient::calls).
+/// Dropping the last clone of the client while expectations are unmet panics.
+///
+/// The client is cheap to clone, and all clones share expectations and history.
+/// Keep a clone to inspect the calls after handing the client to [`DomeneshopClient`](crate::client::DomeneshopClient).
+///
+/// # Example
+/// ```
+/// use domeneshop_client::client::{ DomeneshopClientConfiguration, DomeneshopClient };
+/// use domeneshop_client::credentials::Credentials;
+/// use domeneshop_client::http_client::mock::ClosureMockClient;
+/// use http_types::{ Method, StatusCode };
+///
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # tokio_test::block_on(async {
+/// let mock = ClosureMockClient::new();
+/// mock.expect(Method::Get, "/domains", StatusCode::Ok, "[]");
+///
+/// let client = DomeneshopClient::with_http_client(
+///     Credentials::new("token", "secret"),
+///     mock.clone(),
+///     DomeneshopClientConfiguration {
+///         base_url: Some("https://localhost".to_string()),
+///         ..Default::default()
+///     }
+/// )?;
+/// let domains = client.list_domains().await?;
+///
+/// assert_eq!(domains.len(), 0);
+/// assert_eq!(mock.calls()[0].url, "https://localhost/v0/domains");
+/// #
+/// #     Ok(())
+/// # })
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ClosureMockClient {
+    inner: Arc<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    handler: Option<Box<Handler>>,
+    expectations: Mutex<Vec<Expectation>>,
+    calls: Mutex<Vec<RecordedRequest>>,
+}
+
+/// A call the mock expects to receive, with the response to send
+struct Expectation {
+    method: Method,
+    path: String,
+    status: StatusCode,
+    body: String,
+    remaining: usize,
+}
+
+impl Expectation {
+    fn matches(&self, method: Method, url: &Url) -> bool {
+        if self.remaining == 0 || self.method != method {
+            return false;
+        }
+        let (path, query) = match self.path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (self.path.as_str(), None),
+        };
+        let request_path = format!("/{}", api_segments(url).join("/"));
+        request_path == path && query.map_or(true, |query| url.query() == Some(query))
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "  {} {} -> {} ({} call(s) left)",
+            self.method, self.path, self.status, self.remaining
+        )
+    }
+}
+
+impl ClosureMockClient {
+    /// Creates a mock without a handler. Requests must match an expectation.
+    pub fn new() -> Self {
+        ClosureMockClient::default()
+    }
+
+    /// Creates a mock passing requests not matching an expectation to `handler`
+    pub fn with_handler<H, F>(handler: H) -> Self
+    where
+        H: Fn(Request) -> F + Send + Sync + 'static,
+        F: Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
+    {
+        ClosureMockClient {
+            inner: Arc::new(MockState {
+                handler: Some(Box::new(move |request| Box::pin(handler(request)))),
+                expectations: Mutex::default(),
+                calls: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Expects a single call with the method and path, responding with the status and body.
+    ///
+    /// The path is relative to the API version, e.g. `/domains/1/dns`, also when the base url has a path.
+    /// The query is only compared if included, e.g. `/domains?domain=.no`.
+    pub fn expect(
+        &self,
+        method: Method,
+        path: impl Into<String>,
+        status: StatusCode,
+        body: impl Into<String>,
+    ) -> &Self {
+        self.expect_times(method, path, status, body, 1)
+    }
+
+    /// Like [`expect`](ClosureMockClient::expect), but expects the call `times` times
+    pub fn expect_times(
+        &self,
+        method: Method,
+        path: impl Into<String>,
+        status: StatusCode,
+        body: impl Into<String>,
+        times: usize,
+    ) -> &Self {
+        self.inner.expectations.lock().unwrap().push(Expectation {
+            method,
+            path: path.into(),
+            status,
+            body: body.into(),
+            remaining: times,
+        });
+        self
+    }
+
+    /// Returns every request received so far, in the order they were received
+    pub fn calls(&self) -> Vec<RecordedRequest> {
+        self.inner.calls.lock().unwrap().clone()
+    }
+
+    /// Returns a description of the expectations that have not been met
+    pub fn unmet_expectations(&self) -> Vec<String> {
+        self.inner.unmet_expectations()
+    }
+
+    /// Panics if any expectation has not been met
+    pub fn assert_expectations(&self) {
+        self.inner.assert_expectations();
+    }
+}
+
+impl MockState {
+    fn unmet_expectations(&self) -> Vec<String> {
+        self.expectations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|expectation| expectation.remaining > 0)
+            .map(Expectation::describe)
+            .collect()
+    }
+
+    fn assert_expectations(&self) {
+        let unmet = self.unmet_expectations();
+        if !unmet.is_empty() {
+            panic!(
+                "{} expectation(s) of the mock were not met:\n{}",
+                unmet.len(),
+                unmet.join("\n")
+            );
+        }
+    }
+}
+
+impl Drop for MockState {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            self.assert_expectations();
+        }
+    }
+}
+
+impl HttpClient for ClosureMockClient {
+    async fn execute_request(&self, mut request: Request) -> Result<Response, DomeneshopError> {
+        let body = take_body(request.take_body()).await?;
+        let (recorded_body, body_base64) = encode_body(&body);
+        self.inner.calls.lock().unwrap().push(RecordedRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            headers: collect_headers(request.iter()),
+            body: recorded_body,
+            body_base64,
+        });
+        if !body.is_empty() {
+            request.set_body(body);
+        }
+
+        let canned = {
+            let mut expectations = self.inner.expectations.lock().unwrap();
+            expectations
+                .iter_mut()
+                .find(|expectation| expectation.matches(request.method(), request.url()))
+                .map(|expectation| {
+                    expectation.remaining -= 1;
+                    (expectation.status, expectation.body.clone())
+                })
+        };
+        if let Some((status, body)) = canned {
+            let mut response = Response::new(status);
+            if !body.is_empty() {
+                response.set_body(body);
+            }
+            return Ok(response);
+        }
+
+        match &self.inner.handler {
+            Some(handler) => handler(request).await,
+            None => panic!(
+                "Unexpected request {} {}. Unmet expectations:\n{}",
+                request.method(),
+                request.url(),
+                self.unmet_expectations().join("\n")
+            ),
+        }
+    }
+}
@@ -1,26 +1,24 @@
 use std::fmt::Display;
 
-use async_trait::async_trait;
 use http_types::{Method, Request, Response, StatusCode};
 
-use crate::{errors::to_domain_error, errors::DomeneshopError, http::HttpClient};
+use crate::{errors::DomeneshopError, http::HttpClient};
 
-#[async_trait]
 impl HttpClient for ::reqwest::Client {
     async fn execute_request(&self, mut request: Request) -> Result<Response, DomeneshopError> {
-        let url = request.url();
-        let method = request.method();
-        let mut request_builder = self.request(map_method(method)?, url.clone());
-        for (name, value) in request.iter() {
-            request_builder = request_builder.header(name.as_str(), value.as_str());
+        let mut request_builder =
+            self.request(map_method(request.method())?, request.url().clone());
+        for (name, values) in request.iter() {
+            for value in values.iter() {
+                request_builder = request_builder.header(name.as_str(), value.as_str());
+            }
         }
 
-        let body = request.take_body();
-        if let Some(is_empty) = body.is_empty() {
-            if !is_empty {
-                let body_data = body.into_bytes().await.map_err(to_domain_error)?;
-                request_builder = request_builder.body(body_data);
-            }
+        let body_data = request.take_body().into_bytes().await.map_err(|err| {
+            DomeneshopError::transport("Failed to read request body", err.into_inner())
+        })?;
+        if !body_data.is_empty() {
+            request_builder = request_builder.body(body_data);
         }
 
         let reqwest_request = request_builder
@@ -33,35 +31,102 @@ impl HttpClient for ::reqwest::Client {
             .map_err(|err| map_reqwest_error("Sending request failed", err))?;
 
         let status = rsp.status();
+        let headers = rsp.headers().clone();
         let body = rsp
-            .text()
+            .bytes()
             .await
             .map_err(|err| map_reqwest_error("Failed to extract reqwest body", err))?;
 
-        let mut resp = Response::new(map_status(status)?);
-        resp.set_body(body);
-        Ok(resp)
+        map_response(status, &headers, &body)
     }
 }
 
 fn map_method(method: Method) -> Result<::reqwest::Method, DomeneshopError> {
-    match method {
-        Method::Delete => Ok(::reqwest::Method::DELETE),
-        Method::Get => Ok(::reqwest::Method::GET),
-        Method::Post => Ok(::reqwest::Method::POST),
-        Method::Put => Ok(::reqwest::Method::PUT),
-        _ => Err(DomeneshopError::new(format!("Invalid method: {}", method))),
-    }
+    ::reqwest::Method::from_bytes(method.to_string().as_bytes())
+        .map_err(|err| DomeneshopError::new(format!("Invalid method: {}", method)).with_source(err))
 }
 
 fn map_status(status: ::reqwest::StatusCode) -> Result<StatusCode, DomeneshopError> {
     let status = u16::from(status);
-    StatusCode::try_from(status).map_err(to_domain_error)
+    // http-types cannot represent the status, so it cannot be an `UnexpectedStatus`.
+    // It is not a transport error either, as retrying will not change the status.
+    StatusCode::try_from(status).map_err(|err| {
+        DomeneshopError::new(format!("Unsupported response status {}", status))
+            .with_source(err.into_inner())
+    })
+}
+
+/// Copies the status, all headers and the body of a reqwest-response into an `http-types` response
+fn map_response(
+    status: ::reqwest::StatusCode,
+    headers: &::reqwest::header::HeaderMap,
+    body: &[u8],
+) -> Result<Response, DomeneshopError> {
+    let mut resp = Response::new(map_status(status)?);
+    for (name, value) in headers {
+        // http-types only supports ASCII header values
+        if let Ok(value) = value.to_str() {
+            resp.append_header(name.as_str(), value);
+        }
+    }
+    if !body.is_empty() {
+        let has_content_type = resp.content_type().is_some();
+        resp.set_body(body);
+        // `set_body` adds a content type when the response has none
+        if !has_content_type {
+            resp.remove_header("Content-Type");
+        }
+    }
+    Ok(resp)
 }
 
+/// Errors building the request are [`Configuration`](crate::errors::DomeneshopErrorKind::Configuration)-errors,
+/// as they fail the same way every time. All other errors, e.g. connect, timeout and IO errors, are transport errors.
 pub fn map_reqwest_error(
     context: impl Into<String> + Display,
     error: reqwest::Error,
 ) -> DomeneshopError {
-    DomeneshopError::new(format!("{}: {}", context, error))
+    let message = format!("{}: {}", context, error);
+    if error.is_builder() {
+        DomeneshopError::configuration(message).with_source(error)
+    } else {
+        DomeneshopError::transport(message, error)
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::BlockingHttpClient for ::reqwest::blocking::Client {
+    fn execute_request(&self, mut request: Request) -> Result<Response, DomeneshopError> {
+        let mut request_builder =
+            self.request(map_method(request.method())?, request.url().clone());
+        for (name, values) in request.iter() {
+            for value in values.iter() {
+                request_builder = request_builder.header(name.as_str(), value.as_str());
+            }
+        }
+
+        let body_data =
+            futures_lite::future::block_on(request.take_body().into_bytes()).map_err(|err| {
+                DomeneshopError::transport("Failed to read request body", err.into_inner())
+            })?;
+        if !body_data.is_empty() {
+            request_builder = request_builder.body(body_data);
+        }
+
+        let reqwest_request = request_builder
+            .build()
+            .map_err(|err| map_reqwest_error("Failed to build reqwest request", err))?;
+
+        let rsp = self
+            .execute(reqwest_request)
+            .map_err(|err| map_reqwest_error("Sending request failed", err))?;
+
+        let status = rsp.status();
+        let headers = rsp.headers().clone();
+        let body = rsp
+            .bytes()
+            .map_err(|err| map_reqwest_error("Failed to extract reqwest body", err))?;
+
+        map_response(status, &headers, &body)
+    }
 }
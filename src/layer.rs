@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use http_types::{Request, Response};
+
+use crate::{errors::DomeneshopError, http::DynHttpClient};
+
+#[async_trait]
+/// A middleware wrapping the [`HttpClient`](crate::http::HttpClient) used by the [`DomeneshopClient`](crate::client::DomeneshopClient).
+///
+/// Layers are supplied as part of [`DomeneshopClientConfiguration`](crate::client::DomeneshopClientConfiguration),
+/// and are invoked in the order they are given for every request sent to the API.
+/// Each layer can inspect and modify the outgoing request, pass it on by calling [`Next::run`],
+/// and inspect and modify the returned response.
+/// A layer can also return a response or an error without calling the next layer.
+///
+/// Layers are invoked once for every attempt, i.e. retried requests pass through the layers again.
+/// Layers are dispatched dynamically, and the call to the [`HttpClient`](crate::http::HttpClient) after the last layer is boxed.
+///
+/// # Example
+/// A layer adding a header to every request:
+/// ```
+/// use async_trait::async_trait;
+/// use http_types::{Request, Response};
+/// use domeneshop_client::{errors::DomeneshopError, layer::{Layer, Next}};
+///
+/// struct RequestIdLayer;
+///
+/// #[async_trait]
+/// impl Layer for RequestIdLayer {
+///     async fn handle(&self, mut request: Request, next: Next<'_>) -> Result<Response, DomeneshopError> {
+///         request.insert_header("X-Request-Id", "42");
+///         let response = next.run(request).await?;
+///         println!("Received status {}", response.status());
+///         Ok(response)
+///     }
+/// }
+/// ```
+pub trait Layer: Send + Sync {
+    /// Handles a request. Call `next.run(request)` to pass the request on to the next layer.
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, DomeneshopError>;
+}
+
+/// The remainder of the layer chain, ending with the underlying [`HttpClient`](crate::http::HttpClient).
+///
+/// `Next` is `Copy`, so a layer can pass a request on several times, e.g. to retry it.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    layers: &'a [Box<dyn Layer>],
+    client: &'a dyn DynHttpClient,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(layers: &'a [Box<dyn Layer>], client: &'a dyn DynHttpClient) -> Self {
+        Next { layers, client }
+    }
+
+    /// Passes the request on to the next layer, or to the [`HttpClient`](crate::http::HttpClient) if this is the last layer
+    pub async fn run(self, request: Request) -> Result<Response, DomeneshopError> {
+        match self.layers.split_first() {
+            Some((layer, layers)) => layer.handle(request, Next::new(layers, self.client)).await,
+            None => self.client.execute_request_boxed(request).await,
+        }
+    }
+}
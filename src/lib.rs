@@ -22,24 +22,61 @@
 //!
 //! The client will have functions for all operations in the API.
 //!
+//! The token and secret are kept as [`Credentials`](credentials::Credentials), which are zeroized on drop and redacted in `Debug`-output.
+//! Use [`DomeneshopClient::with_credentials`](client::DomeneshopClient::with_credentials) to supply them directly.
+//!
 //! By default, the `reqwest` feature is enabled.
 //! This uses the [reqwest](reqwest)-crate to perform the requests.
 //! It is possible to supply your own implementation of the [`HttpClient`](http::HttpClient)-trait.
 //!
-//! If so, you must supply the custom implementation as part of [`DomeneshopClientConfiguration`](client::DomeneshopClientConfiguration).
-//! (the `default`-function is not present when including this crate without the `reqwest`-feature).
+//! The client is generic over the implementation, so requests are dispatched statically.
+//! Pass a custom implementation to [`DomeneshopClient::with_http_client`](client::DomeneshopClient::with_http_client)
+//! (without the `reqwest`-feature, [`DomeneshopClient::new`](client::DomeneshopClient::new) fails, since there is no default implementation).
+//! Use [`BoxedHttpClient`](http::BoxedHttpClient) to erase the type of the implementation.
 //!
 //! ```rust
-//! # let token = "".to_string();
-//! # let secret = "".to_string();
 //! # let client = reqwest::Client::new();
 //! # use domeneshop_client::client::{ DomeneshopClient, DomeneshopClientConfiguration };
+//! use domeneshop_client::credentials::Credentials;
+//!
+//! let client = DomeneshopClient::with_http_client(
+//!     Credentials::new("token", "secret"),
+//!     client,
+//!     DomeneshopClientConfiguration::default(),
+//! );
+//! ```
+//!
+//! ## Loading credentials
+//! [`DomeneshopClient::from_env`](client::DomeneshopClient::from_env) reads the credentials from the environment variables
+//! `DOMENESHOP_TOKEN` and `DOMENESHOP_SECRET`, and optionally the base url from `DOMENESHOP_BASE_URL`.
+//!
+//! [`DomeneshopClient::from_config_file`](client::DomeneshopClient::from_config_file) reads a named profile from
+//! `$XDG_CONFIG_HOME/domeneshop/config.toml`. The file must not be readable by everyone.
+//!
+//! ```no_run
+//! # fn main() -> Result<(), domeneshop_client::errors::DomeneshopError> {
+//! use domeneshop_client::client::DomeneshopClient;
+//!
+//! let client = DomeneshopClient::from_config_file("default")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Using the builder
+//! [`DomeneshopClient::builder`](client::DomeneshopClient::builder) validates all settings up front,
+//! and can configure timeouts, a proxy and TLS for the default `reqwest`-client.
 //!
-//! let client = DomeneshopClient::new(token, secret, DomeneshopClientConfiguration {
-//!     user_agent: None,
-//!     base_url: None,
-//!     underlying_client: Some(Box::new(client)),
-//! });
+//! ```
+//! # fn main() -> Result<(), domeneshop_client::errors::DomeneshopError> {
+//! use std::time::Duration;
+//! use domeneshop_client::client::DomeneshopClient;
+//!
+//! let client = DomeneshopClient::builder()
+//!     .credentials("token", "secret")
+//!     .timeout(Duration::from_secs(30))
+//!     .build()?;
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! ## Additional configuration
@@ -47,24 +84,92 @@
 //! This can be used to override some settings:
 //! - `user_agent`: Replace the default user agent (defaults to this crate's github-url and crate version).
 //! - `base_url`: Used to override the base url of the API. Can be used for testing or proxying requests.
-//! - `underlying_client`: An implementation of the trait [`HttpClient`](http::HttpClient) to use instead of the default `reqwest`-implementation.
+//! - `retry_policy`: Decides if and how failed requests are retried. See [`RetryPolicy`](retry::RetryPolicy).
+//! - `rate_limiter`: Limits the rate of requests sent by the client and pauses requests when the API responds with `429 Too Many Requests`. See [`RateLimiter`](rate_limit::RateLimiter).
+//! - `cache`: Caches responses from read endpoints, invalidated by mutations through the client. See [`ResponseCache`](cache::ResponseCache).
+//! - `audit`: Sends an event to an audit sink for every mutating call, e.g. a JSON-lines file. See [`AuditLog`](audit::AuditLog).
+//! - `dry_run`: Records mutations instead of sending them, while reads are sent as usual. See [`DryRun`](dry_run::DryRun).
+//! - `layers`: Middleware wrapping the underlying client, e.g. for logging, metrics or header injection. See [`Layer`](layer::Layer).
+//!
+//! ## Unsupported endpoints
+//! Endpoints without a dedicated operation can be called through [`request`](client::DomeneshopClient::request),
+//! which serializes the body and deserializes the response, or [`request_raw`](client::DomeneshopClient::request_raw),
+//! which returns the response as is. Both use the same authentication, base url and error handling as the other operations.
+//!
+//! ## Zone files
+//! [`export_zone`](client::DomeneshopClient::export_zone) renders the DNS records of a domain as an RFC 1035 zone file,
+//! e.g. for backups or for reviewing changes. The output is sorted, so exporting the same records twice gives the same file.
+//!
+//! To move a domain from another provider, parse its zone file with [`parse_zone`](zone::parse_zone),
+//! check the plan from [`preview_zone_import`](client::DomeneshopClient::preview_zone_import)
+//! and add the records with [`import_zone`](client::DomeneshopClient::import_zone).
+//!
+//! ## Errors
+//! All operations return [`DomeneshopError`](errors::DomeneshopError).
+//! Use [`kind`](errors::DomeneshopError::kind) to find out what went wrong, e.g. an error returned by the API or a response with an unexpected status.
+//! The underlying cause, if any, is available through [`std::error::Error::source`].
+//! DNS records are checked with [`validate`](endpoints::dns::DnsRecordData::validate) before they are added or updated,
+//! and invalid records fail with [`InvalidRecord`](errors::DomeneshopErrorKind::InvalidRecord) without being sent.
 //!
 //! # Testing
 //! If you want to test code using this client, enable the `mock` feature.
-//! This will make a new [`MockClient`](http_client::mock::MockClient) available that can be passed to the client using the `underlying_client` ocnfiguration option.
+//! This will make a new [`MockClient`](http_client::mock::MockClient) available that can be passed to the client using [`with_http_client`](client::DomeneshopClient::with_http_client).
+//! With the `blocking`-feature enabled, [`BlockingMockClient`](http_client::mock::BlockingMockClient) can be used for the blocking client.
+//! [`ClosureMockClient`](http_client::mock::ClosureMockClient) accepts closures, responds to expected calls with canned responses,
+//! records every request and panics on drop if an expectation was not met.
+//!
+//! The `mock` feature also adds [`RecordingClient`](http_client::cassette::RecordingClient), which records a session against the real API to a cassette file,
+//! and [`ReplayClient`](http_client::cassette::ReplayClient), which serves the cassette back offline.
+//! For tests that need state across calls, [`FakeDomeneshop`](http_client::fake::FakeDomeneshop) keeps domains, records, forwards and invoices in memory
+//! and responds like the API, including its error responses.
+//!
+//! With the `emulator` feature, the `domeneshop-emulator` binary serves the same fake over HTTP on localhost, seeded from a JSON fixture file.
+//! Use its address as `base_url` to test against it through the real HTTP client, also from other languages.
 //!
 //! # Features
 //! - `reqwest` (default feature): Uses [`reqwest`](reqwest) to perform the requests. Consumers must supply their own implementation of [`HttpClient`](http::HttpClient) if this is disabled.
+//! - `rustls-tls`: Makes [`TlsBackend::Rustls`](client::TlsBackend::Rustls) available to the builder.
+//! - `native-tls`: Makes [`TlsBackend::NativeTls`](client::TlsBackend::NativeTls) available to the builder.
 //! - `mock`: Adds [`MockClient`](http_client::mock::MockClient) that can be used for testing.
+//! - `blocking`: Adds a blocking [`DomeneshopClient`](blocking::DomeneshopClient) for use in synchronous code.
+//!   With the `reqwest`-feature enabled, it uses the blocking client of [`reqwest`](reqwest).
+//! - `tracing`: Emits a [`tracing`](https://docs.rs/tracing) span for every API call, with the HTTP method, url path, ids, response status and latency as fields.
+//!   Errors are recorded as events in the span. Credentials are never recorded.
+//! - `emulator`: Adds [`Emulator`](emulator::Emulator), a local HTTP server emulating the API, and the `domeneshop-emulator` binary.
+//!   Implies `mock`.
 //!
 //! [reqwest]: https://crates.io/crates/reqwest
 
+/// Module for auditing mutating calls
+pub mod audit;
+/// Module containing the blocking DomeneshopClient. Only available when the `blocking`-feature is enabled.
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub mod blocking;
+/// Module for caching responses from read endpoints
+pub mod cache;
 /// Module containing the DomeneshopClient
 pub mod client;
+/// Module for the credentials used to authenticate against the API
+pub mod credentials;
+/// Module for recording mutations instead of sending them
+pub mod dry_run;
+/// Module for the local API emulator. Only available when the `emulator`-feature is enabled.
+#[cfg(feature = "emulator")]
+#[cfg_attr(docsrs, doc(cfg(feature = "emulator")))]
+pub mod emulator;
 /// Module for error structs
 pub mod errors;
 /// Module for http-specifics (HttpClient-trait)
 pub mod http;
+/// Module for middleware layers wrapping the HttpClient
+pub mod layer;
+/// Module for client-side rate limiting
+pub mod rate_limit;
+/// Module for retrying failed requests
+pub mod retry;
+/// Module for exporting and importing DNS records as zone files
+pub mod zone;
 
 /// Module containing clients implementing the HttpClient-trait
 pub mod http_client {
@@ -72,9 +177,19 @@ pub mod http_client {
     #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
     pub(crate) mod reqwest;
 
+    /// Clients recording and replaying sessions for testing. Only available when the `mock`-feature is enabled.
+    #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+    #[cfg(feature = "mock")]
+    pub mod cassette;
+
+    /// Stateful in-memory fake of the API for testing. Only available when the `mock`-feature is enabled.
+    #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+    #[cfg(feature = "mock")]
+    pub mod fake;
+
     /// Mock-client for testing. Only available when the `mock`-feature is enabled.
     #[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
-    #[cfg(any(feature = "mock"))]
+    #[cfg(feature = "mock")]
     pub mod mock;
 }
 
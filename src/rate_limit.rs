@@ -0,0 +1,234 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use http_types::{other::RetryAfter, Response, StatusCode};
+
+/// Wait used when the API responds with `429 Too Many Requests` without a `Retry-After`-header
+const DEFAULT_THROTTLE_WAIT: Duration = Duration::from_secs(1);
+
+/// The longest wait honoured from a `Retry-After`-header or [`RateLimiter::pause_for`]
+const MAX_PAUSE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A client-side token bucket rate limiter.
+///
+/// Every request consumes one token. Tokens are refilled at `requests_per_second`, and at most `burst` tokens are kept.
+/// When the API responds with `429 Too Many Requests`, all requests are paused until the time given in the `Retry-After`-header has passed.
+/// The throttled request itself is retried according to the [`RetryPolicy`](crate::retry::RetryPolicy) of the client.
+///
+/// The limiter is cheap to clone, and all clones share the same budget.
+/// Callers sharing one [`DomeneshopClient`](crate::client::DomeneshopClient) therefore share the same budget,
+/// and the same limiter can be supplied to several clients.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::rate_limit::RateLimiter;
+///
+/// // At most 5 requests per second, allowing bursts of 10 requests
+/// let limiter = RateLimiter::new(5.0, 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter.
+    ///
+    /// # Panics
+    /// Panics if `requests_per_second` is not positive or `burst` is zero.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "requests_per_second must be positive"
+        );
+        assert!(burst > 0, "burst must be at least 1");
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(TokenBucket::new(
+                requests_per_second,
+                burst,
+                Instant::now(),
+            ))),
+        }
+    }
+
+    /// Waits until a request may be sent, and consumes one token
+    pub async fn acquire(&self) {
+        loop {
+            let wait = match self.bucket.lock().unwrap().try_acquire(Instant::now()) {
+                Ok(()) => return,
+                Err(wait) => wait,
+            };
+            futures_timer::Delay::new(wait).await;
+        }
+    }
+
+    /// Pauses all requests until `wait` has passed. Waits longer than a day are shortened to a day.
+    pub fn pause_for(&self, wait: Duration) {
+        let now = Instant::now();
+        if let Some(until) = now.checked_add(wait.min(MAX_PAUSE)) {
+            self.bucket.lock().unwrap().pause_until(until);
+        }
+    }
+
+    pub(crate) fn observe(&self, response: &Response) {
+        if response.status() == StatusCode::TooManyRequests {
+            self.pause_for(retry_after(response).unwrap_or(DEFAULT_THROTTLE_WAIT));
+        }
+    }
+}
+
+/// Reads the `Retry-After`-header of a response as a duration from now, at most a day
+pub(crate) fn retry_after(response: &Response) -> Option<Duration> {
+    let header = response.header("Retry-After")?.last();
+    if let Ok(seconds) = header.as_str().parse::<u64>() {
+        return Some(Duration::from_secs(seconds).min(MAX_PAUSE));
+    }
+
+    let retry_after = RetryAfter::from_headers(response).ok()??;
+    Some(
+        retry_after
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO)
+            .min(MAX_PAUSE),
+    )
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    requests_per_second: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    fn new(requests_per_second: f64, burst: u32, now: Instant) -> Self {
+        TokenBucket {
+            requests_per_second,
+            burst: burst as f64,
+            tokens: burst as f64,
+            last_refill: now,
+            paused_until: None,
+        }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        if let Some(paused_until) = self.paused_until {
+            if paused_until > now {
+                return Err(paused_until - now);
+            }
+            self.paused_until = None;
+        }
+
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.requests_per_second).min(self.burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.requests_per_second,
+            ))
+        }
+    }
+
+    fn pause_until(&mut self, until: Instant) {
+        self.paused_until = Some(match self.paused_until {
+            Some(paused_until) if paused_until > until => paused_until,
+            _ => until,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use http_types::{Response, StatusCode};
+
+    use super::{retry_after, RateLimiter, TokenBucket, MAX_PAUSE};
+
+    #[test]
+    fn burst_is_available_immediately() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1.0, 3, now);
+
+        assert!(bucket.try_acquire(now).is_ok());
+        assert!(bucket.try_acquire(now).is_ok());
+        assert!(bucket.try_acquire(now).is_ok());
+        assert_eq!(bucket.try_acquire(now), Err(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn tokens_are_refilled_over_time() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 1, now);
+
+        assert!(bucket.try_acquire(now).is_ok());
+        assert_eq!(bucket.try_acquire(now), Err(Duration::from_millis(500)));
+        assert!(bucket.try_acquire(now + Duration::from_millis(500)).is_ok());
+    }
+
+    #[test]
+    fn tokens_do_not_exceed_burst() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, 2, now);
+
+        let later = now + Duration::from_secs(10);
+        assert!(bucket.try_acquire(later).is_ok());
+        assert!(bucket.try_acquire(later).is_ok());
+        assert!(bucket.try_acquire(later).is_err());
+    }
+
+    #[test]
+    fn pause_blocks_until_passed() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, 10, now);
+
+        bucket.pause_until(now + Duration::from_secs(2));
+        bucket.pause_until(now + Duration::from_secs(1));
+
+        assert_eq!(bucket.try_acquire(now), Err(Duration::from_secs(2)));
+        assert!(bucket.try_acquire(now + Duration::from_secs(2)).is_ok());
+    }
+
+    #[test]
+    fn retry_after_reads_seconds() {
+        let mut response = Response::new(StatusCode::TooManyRequests);
+        response.insert_header("Retry-After", "3");
+
+        assert_eq!(retry_after(&response), Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn missing_retry_after_returns_none() {
+        let response = Response::new(StatusCode::TooManyRequests);
+
+        assert_eq!(retry_after(&response), None);
+    }
+
+    #[test]
+    fn absurd_retry_after_is_clamped() {
+        let mut response = Response::new(StatusCode::TooManyRequests);
+        response.insert_header("Retry-After", "18446744073709551615");
+
+        assert_eq!(retry_after(&response), Some(MAX_PAUSE));
+    }
+
+    #[test]
+    fn absurd_pause_does_not_overflow() {
+        let limiter = RateLimiter::new(1.0, 1);
+
+        limiter.pause_for(Duration::MAX);
+
+        let mut response = Response::new(StatusCode::TooManyRequests);
+        response.insert_header("Retry-After", "18446744073709551615");
+        limiter.observe(&response);
+    }
+}
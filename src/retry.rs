@@ -0,0 +1,220 @@
+use std::{future::Future, time::Duration};
+
+use http_types::{Method, Request, Response, StatusCode};
+
+use crate::{
+    errors::{DomeneshopError, DomeneshopErrorKind},
+    rate_limit::retry_after,
+};
+
+/// Policy deciding if and how failed requests are retried.
+///
+/// By default, idempotent requests (`GET`, `PUT` and `DELETE`) are retried up to 3 times in total
+/// when the request fails with a transport error or one of the statuses `429`, `500`, `502`, `503` or `504`.
+/// Non-idempotent requests (`POST`, e.g. `add_dns_record` and `add_forward`) are only retried when `retry_non_idempotent` is set.
+/// If the response contains a `Retry-After`-header, the client waits at least that long before retrying,
+/// but never longer than `max_backoff`.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use domeneshop_client::retry::RetryPolicy;
+///
+/// let policy = RetryPolicy {
+///     max_attempts: 5,
+///     initial_backoff: Duration::from_millis(500),
+///     ..RetryPolicy::default()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Backoff before the first retry. Doubled for each subsequent retry.
+    pub initial_backoff: Duration,
+    /// Upper limit of the backoff between two attempts
+    pub max_backoff: Duration,
+    /// Randomizes each backoff to between half and all of the computed value
+    pub jitter: bool,
+    /// Response statuses that cause a retry
+    pub retryable_statuses: Vec<StatusCode>,
+    /// Decides if an error returned from the [`HttpClient`](crate::http::HttpClient) causes a retry.
+    /// Defaults to [`is_transport_error`].
+    pub retryable_errors: fn(&DomeneshopError) -> bool,
+    /// Also retries non-idempotent requests (`POST`)
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable_statuses: vec![
+                StatusCode::TooManyRequests,
+                StatusCode::InternalServerError,
+                StatusCode::BadGateway,
+                StatusCode::ServiceUnavailable,
+                StatusCode::GatewayTimeout,
+            ],
+            retryable_errors: is_transport_error,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn disabled() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Returns `true` if requests with the given method may be retried
+    pub fn allows_retry(&self, method: Method) -> bool {
+        if self.max_attempts <= 1 {
+            return false;
+        }
+        match method {
+            Method::Get | Method::Put | Method::Delete | Method::Head | Method::Options => true,
+            _ => self.retry_non_idempotent,
+        }
+    }
+
+    /// Computes the backoff before the given retry (starting at 1)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if self.jitter {
+            let half = backoff / 2;
+            half + backoff.mul_f64(fastrand::f64()) / 2
+        } else {
+            backoff
+        }
+    }
+
+    fn should_retry(&self, result: &Result<Response, DomeneshopError>) -> bool {
+        match result {
+            Ok(response) => self.retryable_statuses.contains(&response.status()),
+            Err(err) => (self.retryable_errors)(err),
+        }
+    }
+}
+
+/// Default for [`RetryPolicy::retryable_errors`]. Retries all errors of kind [`Transport`](DomeneshopErrorKind::Transport).
+pub fn is_transport_error(error: &DomeneshopError) -> bool {
+    error.kind() == &DomeneshopErrorKind::Transport
+}
+
+pub(crate) async fn execute_with_retry<E, F>(
+    policy: &RetryPolicy,
+    mut request: Request,
+    execute: E,
+) -> Result<Response, DomeneshopError>
+where
+    E: Fn(Request) -> F,
+    F: Future<Output = Result<Response, DomeneshopError>>,
+{
+    if !policy.allows_retry(request.method()) {
+        return execute(request).await;
+    }
+
+    let body = match request.is_empty() {
+        Some(true) => None,
+        _ => Some(request.take_body().into_bytes().await.map_err(|err| {
+            DomeneshopError::transport("Failed to read request body", err.into_inner())
+        })?),
+    };
+
+    let mut attempt = 1;
+    loop {
+        let mut attempt_request = request.clone();
+        if let Some(body) = &body {
+            attempt_request.set_body(body.clone());
+        }
+
+        let result = execute(attempt_request).await;
+        if attempt >= policy.max_attempts || !policy.should_retry(&result) {
+            return result;
+        }
+
+        let mut backoff = policy.backoff(attempt);
+        if let Some(retry_after) = result.as_ref().ok().and_then(retry_after) {
+            backoff = backoff.max(retry_after.min(policy.max_backoff));
+        }
+        futures_timer::Delay::new(backoff).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use http_types::Method;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn backoff_doubles_until_max() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(300));
+        assert_eq!(policy.backoff(40), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn backoff_with_jitter_stays_within_bounds() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            ..RetryPolicy::default()
+        };
+
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(50));
+            assert!(backoff <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn only_idempotent_methods_are_retried_by_default() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.allows_retry(Method::Get));
+        assert!(policy.allows_retry(Method::Put));
+        assert!(policy.allows_retry(Method::Delete));
+        assert!(!policy.allows_retry(Method::Post));
+    }
+
+    #[test]
+    fn non_idempotent_methods_are_retried_when_enabled() {
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..RetryPolicy::default()
+        };
+
+        assert!(policy.allows_retry(Method::Post));
+    }
+
+    #[test]
+    fn disabled_policy_never_retries() {
+        let policy = RetryPolicy::disabled();
+
+        assert!(!policy.allows_retry(Method::Get));
+    }
+}
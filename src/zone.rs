@@ -0,0 +1,387 @@
+use std::{collections::HashMap, fmt::Write};
+
+use url::Host;
+
+use crate::{
+    client::DomeneshopClient,
+    endpoints::{
+        dns::{txt_strings, DnsRecordData, Ttl, MAX_TXT_STRING_LENGTH},
+        domains::DomainId,
+    },
+    errors::DomeneshopError,
+    http::HttpClient,
+};
+
+mod import;
+mod parse;
+
+pub use import::{
+    ImportAction, ImportOutcome, ImportPlan, ImportReport, ImportResult, PlannedRecord,
+};
+pub use parse::{parse_zone, ParsedZone, UnsupportedRecord, ZoneParseError};
+
+/// Renders records as an RFC 1035 zone file for the domain `origin`.
+///
+/// The zone starts with `$ORIGIN` and `$TTL`, where `$TTL` is the most common TTL of the records.
+/// Records are sorted by name, type and data, so the same records always give the same output.
+/// Names are relative to the origin and hostnames in the data are fully qualified.
+/// ANAME-records are not part of the standard, and are rendered with `ANAME` as their type.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::endpoints::dns::{DnsRecordData, TXTRecordData, Ttl};
+/// use domeneshop_client::zone::render_zone;
+///
+/// let records = vec![DnsRecordData::TXT(TXTRecordData {
+///     host: "@".to_string(),
+///     ttl: Ttl::HOUR,
+///     data: "v=spf1 -all".to_string(),
+/// })];
+///
+/// assert_eq!(
+///     render_zone("example.com", &records),
+///     "$ORIGIN example.com.\n$TTL 3600\n@ IN TXT   \"v=spf1 -all\"\n"
+/// );
+/// ```
+pub fn render_zone(origin: &str, records: &[DnsRecordData]) -> String {
+    let default_ttl = most_common_ttl(records);
+
+    let mut lines: Vec<ZoneLine> = records
+        .iter()
+        .map(|record| ZoneLine {
+            sort_key: sort_key(record.host()),
+            name: render_name(record.host()),
+            ttl: (record.ttl() != default_ttl).then(|| record.ttl().to_string()),
+            dns_type: record.dns_type().to_string(),
+            rdata: render_rdata(record),
+        })
+        .collect();
+    lines.sort_by(|a, b| {
+        (&a.sort_key, &a.dns_type, &a.rdata).cmp(&(&b.sort_key, &b.dns_type, &b.rdata))
+    });
+
+    let name_width = lines.iter().map(|line| line.name.len()).max().unwrap_or(0);
+    let ttl_width = lines
+        .iter()
+        .filter_map(|line| line.ttl.as_ref().map(String::len))
+        .max();
+
+    let mut zone = String::new();
+    _ = writeln!(zone, "$ORIGIN {}", absolute_name(origin));
+    _ = writeln!(zone, "$TTL {}", default_ttl);
+    for line in lines {
+        _ = write!(zone, "{:<width$} ", line.name, width = name_width);
+        if let Some(ttl_width) = ttl_width {
+            _ = write!(
+                zone,
+                "{:<width$} ",
+                line.ttl.unwrap_or_default(),
+                width = ttl_width
+            );
+        }
+        _ = writeln!(zone, "IN {:<5} {}", line.dns_type, line.rdata);
+    }
+    // Types shorter than the column are padded, which is noise at the end of a line
+    zone.lines()
+        .map(|line| line.trim_end().to_string() + "\n")
+        .collect()
+}
+
+struct ZoneLine {
+    sort_key: Vec<String>,
+    name: String,
+    ttl: Option<String>,
+    dns_type: String,
+    rdata: String,
+}
+
+/// Operations concerning zone files
+impl<H: HttpClient> DomeneshopClient<H> {
+    /// Exports all DNS records of a domain as an RFC 1035 zone file.
+    ///
+    /// See [`render_zone`] for the format.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self), err))]
+    pub async fn export_zone(&self, domain_id: DomainId) -> Result<String, DomeneshopError> {
+        let domain = self.get_domain(domain_id).await?;
+        let records: Vec<DnsRecordData> = self
+            .list_dns_records(domain_id)
+            .await?
+            .into_iter()
+            .map(|record| record.data)
+            .collect();
+
+        Ok(render_zone(&domain.domain, &records))
+    }
+}
+
+/// The TTL most records use, preferring the lowest on ties. One hour if there are no records
+fn most_common_ttl(records: &[DnsRecordData]) -> Ttl {
+    let mut counts: HashMap<Ttl, usize> = HashMap::new();
+    for record in records {
+        *counts.entry(record.ttl()).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|(a_ttl, a_count), (b_ttl, b_count)| a_count.cmp(b_count).then(b_ttl.cmp(a_ttl)))
+        .map(|(ttl, _)| ttl)
+        .unwrap_or(Ttl::HOUR)
+}
+
+/// Sorts the apex first, and subdomains right after their parent
+fn sort_key(host: &str) -> Vec<String> {
+    if host == "@" {
+        return Vec::new();
+    }
+    host.rsplit('.').map(str::to_lowercase).collect()
+}
+
+fn render_name(host: &str) -> String {
+    if host == "@" {
+        return host.to_string();
+    }
+    host.split('.')
+        .map(render_label)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Hostnames in the data are fully qualified, unless they refer to the origin
+fn render_target(target: &str) -> String {
+    match target {
+        "@" | "." => target.to_string(),
+        _ => absolute_name(target),
+    }
+}
+
+fn absolute_name(name: &str) -> String {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    format!("{}.", render_name(name))
+}
+
+/// Renders internationalized labels in their ASCII form and escapes characters with special meaning
+fn render_label(label: &str) -> String {
+    let label = if label.is_ascii() {
+        label.to_string()
+    } else {
+        match Host::parse(label) {
+            Ok(Host::Domain(ascii)) if !ascii.contains('.') => ascii,
+            _ => label.to_string(),
+        }
+    };
+    let mut rendered = String::with_capacity(label.len());
+    for byte in label.bytes() {
+        match byte {
+            b'.' | b'"' | b';' | b'(' | b')' | b'\\' | b'@' | b'$' => {
+                rendered.push('\\');
+                rendered.push(byte as char);
+            }
+            b'!'..=b'~' => rendered.push(byte as char),
+            _ => _ = write!(rendered, "\\{:03}", byte),
+        }
+    }
+    rendered
+}
+
+fn render_rdata(record: &DnsRecordData) -> String {
+    match record {
+        DnsRecordData::A(record) => record.data.to_string(),
+        DnsRecordData::AAAA(record) => record.data.to_string(),
+        DnsRecordData::CNAME(record) => render_target(&record.data),
+        DnsRecordData::ANAME(record) => render_target(&record.data),
+        DnsRecordData::NS(record) => render_target(&record.data),
+        DnsRecordData::MX(record) => {
+            format!("{} {}", record.priority, render_target(&record.data))
+        }
+        DnsRecordData::SRV(record) => format!(
+            "{} {} {} {}",
+            record.priority,
+            record.weight,
+            record.port,
+            render_target(&record.data)
+        ),
+        DnsRecordData::TXT(record) => render_txt(&record.data),
+        DnsRecordData::CAA(record) => {
+            format!("{} {} {}", record.flags, record.tag, quote(&record.data))
+        }
+        DnsRecordData::DS(record) => format!(
+            "{} {} {} {}",
+            record.tag, record.alg, record.digest, record.data
+        ),
+        DnsRecordData::TLSA(record) => format!(
+            "{} {} {} {}",
+            record.usage, record.selector, record.dtype, record.data
+        ),
+    }
+}
+
+/// Renders TXT-data as quoted strings. Data that is not valid is kept as text, split where it is too long
+fn render_txt(data: &str) -> String {
+    let strings = txt_strings(data).unwrap_or_else(|_| vec![data.to_string()]);
+    strings
+        .iter()
+        .flat_map(|string| split_at_length(string, MAX_TXT_STRING_LENGTH))
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn split_at_length(string: &str, length: usize) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = string;
+    while rest.len() > length {
+        let mut end = length;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (part, remaining) = rest.split_at(end);
+        parts.push(part);
+        rest = remaining;
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Quotes a character-string, escaping quotes, backslashes and control characters
+fn quote(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push('"');
+    for c in string.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            c if c.is_ascii_control() => _ = write!(quoted, "\\{:03}", c as u8),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::dns::{
+        AAAARecordData, ARecordData, CAARecordData, CAATag, CNAMERecordData, DnsRecordData,
+        MXRecordData, SRVRecordData, TXTRecordData, Ttl,
+    };
+
+    use super::{quote, render_zone, split_at_length};
+
+    fn txt(host: &str, data: &str) -> DnsRecordData {
+        DnsRecordData::TXT(TXTRecordData {
+            host: host.to_string(),
+            ttl: Ttl::HOUR,
+            data: data.to_string(),
+        })
+    }
+
+    #[test]
+    fn records_are_sorted_and_aligned() {
+        let records = vec![
+            DnsRecordData::CNAME(CNAMERecordData {
+                host: "www".to_string(),
+                ttl: Ttl::HOUR,
+                data: "example.net".to_string(),
+            }),
+            DnsRecordData::SRV(SRVRecordData {
+                host: "_sip._tcp".to_string(),
+                ttl: Ttl::DAY,
+                data: "sip.example.com".to_string(),
+                priority: 10,
+                weight: 5,
+                port: 5060,
+            }),
+            DnsRecordData::MX(MXRecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "mail.example.com".to_string(),
+                priority: 10,
+            }),
+            DnsRecordData::AAAA(AAAARecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "2001:db8::1".parse().unwrap(),
+            }),
+            DnsRecordData::A(ARecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "192.0.2.1".parse().unwrap(),
+            }),
+            DnsRecordData::A(ARecordData {
+                host: "dev.www".to_string(),
+                ttl: Ttl::HOUR,
+                data: "192.0.2.2".parse().unwrap(),
+            }),
+        ];
+
+        assert_eq!(
+            render_zone("example.com.", &records),
+            "$ORIGIN example.com.\n\
+             $TTL 3600\n\
+             @               IN A     192.0.2.1\n\
+             @               IN AAAA  2001:db8::1\n\
+             @               IN MX    10 mail.example.com.\n\
+             _sip._tcp 86400 IN SRV   10 5 5060 sip.example.com.\n\
+             www             IN CNAME example.net.\n\
+             dev.www         IN A     192.0.2.2\n"
+        );
+    }
+
+    #[test]
+    fn output_does_not_depend_on_input_order() {
+        let mut records = vec![txt("b", "two"), txt("a", "one"), txt("a", "zero")];
+        let zone = render_zone("example.com", &records);
+        records.reverse();
+
+        assert_eq!(render_zone("example.com", &records), zone);
+    }
+
+    #[test]
+    fn txt_and_caa_data_is_quoted() {
+        let long = "a".repeat(300);
+        let records = vec![
+            txt("plain", "v=spf1 include:example.com -all"),
+            txt("quoted", "\"first\" \"say \\\"hi\\\"\""),
+            txt("long", &long),
+            DnsRecordData::CAA(CAARecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "letsencrypt.org".to_string(),
+                flags: 0,
+                tag: CAATag::Issue,
+            }),
+        ];
+
+        let zone = render_zone("example.com", &records);
+
+        assert!(zone.contains("@      IN CAA   0 issue \"letsencrypt.org\"\n"));
+        assert!(zone.contains("plain  IN TXT   \"v=spf1 include:example.com -all\"\n"));
+        assert!(zone.contains("quoted IN TXT   \"first\" \"say \\\"hi\\\"\"\n"));
+        assert!(zone.contains(&format!(
+            "long   IN TXT   \"{}\" \"{}\"\n",
+            &long[..255],
+            &long[255..]
+        )));
+    }
+
+    #[test]
+    fn names_are_escaped_and_converted_to_ascii() {
+        let zone = render_zone("blåbær.no", &[txt("bad;name", "x"), txt("søk", "y")]);
+
+        assert_eq!(
+            zone,
+            "$ORIGIN xn--blbr-roah.no.\n\
+             $TTL 3600\n\
+             bad\\;name  IN TXT   \"x\"\n\
+             xn--sk-lka IN TXT   \"y\"\n"
+        );
+    }
+
+    #[test]
+    fn strings_are_quoted_and_split() {
+        assert_eq!(quote("a\"b\\c\td"), "\"a\\\"b\\\\c\\009d\"");
+        assert_eq!(split_at_length("æøå", 3), vec!["æ", "ø", "å"]);
+        assert_eq!(split_at_length("", 3), vec![""]);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    client::DomeneshopClient,
+    endpoints::{
+        dns::{DnsId, DnsRecordData, RecordViolation},
+        domains::DomainId,
+    },
+    errors::DomeneshopError,
+    http::HttpClient,
+};
+
+/// What [`import_zone`](DomeneshopClient::import_zone) will do with a record
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ImportAction {
+    /// The record will be added
+    Create,
+    /// An identical record with this id already exists, so the record is skipped
+    AlreadyExists(DnsId),
+    /// The record is skipped because it does not pass [`DnsRecordData::validate`]
+    Invalid(Vec<RecordViolation>),
+}
+
+/// A record to import and what will be done with it
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlannedRecord {
+    /// The record to import
+    pub record: DnsRecordData,
+    /// What will be done with the record
+    pub action: ImportAction,
+}
+
+/// The preview of an import, returned by [`preview_zone_import`](DomeneshopClient::preview_zone_import)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportPlan {
+    /// The domain the records are imported to
+    pub domain_id: DomainId,
+    /// The records to import, in the order they will be added
+    pub records: Vec<PlannedRecord>,
+}
+
+impl ImportPlan {
+    /// The records that will be added
+    pub fn to_create(&self) -> impl Iterator<Item = &DnsRecordData> {
+        self.records
+            .iter()
+            .filter(|planned| planned.action == ImportAction::Create)
+            .map(|planned| &planned.record)
+    }
+}
+
+/// The outcome of importing a single record
+#[derive(Clone, Debug)]
+pub enum ImportOutcome {
+    /// The record was added with this id
+    Created(DnsId),
+    /// An identical record with this id already existed, so the record was skipped
+    AlreadyExists(DnsId),
+    /// The record was skipped because it is invalid
+    Invalid(Vec<RecordViolation>),
+    /// The API rejected the record, or the request failed
+    Failed(DomeneshopError),
+}
+
+/// A record of the plan and what happened to it
+#[derive(Clone, Debug)]
+pub struct ImportResult {
+    /// The imported record
+    pub record: DnsRecordData,
+    /// What happened to the record
+    pub outcome: ImportOutcome,
+}
+
+/// The result of [`import_zone`](DomeneshopClient::import_zone), with one entry per record in the plan
+#[derive(Clone, Debug)]
+pub struct ImportReport {
+    /// The outcome of every record, in the order of the plan
+    pub results: Vec<ImportResult>,
+}
+
+impl ImportReport {
+    /// The records that were not imported because they were invalid or rejected
+    pub fn failures(&self) -> impl Iterator<Item = &ImportResult> {
+        self.results.iter().filter(|result| {
+            matches!(
+                result.outcome,
+                ImportOutcome::Invalid(_) | ImportOutcome::Failed(_)
+            )
+        })
+    }
+
+    /// Whether every record was added or already existed
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// Operations concerning zone files
+impl<H: HttpClient> DomeneshopClient<H> {
+    /// Previews importing records to a domain, e.g. the records of [`parse_zone`](crate::zone::parse_zone).
+    ///
+    /// Nothing is changed. The plan tells which records will be added, which already exist and which are invalid.
+    /// Pass it to [`import_zone`](DomeneshopClient::import_zone) to add the records.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, records), err))]
+    pub async fn preview_zone_import(
+        &self,
+        domain_id: DomainId,
+        records: Vec<DnsRecordData>,
+    ) -> Result<ImportPlan, DomeneshopError> {
+        let existing = self.list_dns_records(domain_id).await?;
+
+        let records = records
+            .into_iter()
+            .map(|record| {
+                let violations = record.validate();
+                let action = if !violations.is_empty() {
+                    ImportAction::Invalid(violations)
+                } else if let Some(existing) =
+                    existing.iter().find(|existing| existing.data == record)
+                {
+                    ImportAction::AlreadyExists(existing.id)
+                } else {
+                    ImportAction::Create
+                };
+                PlannedRecord { record, action }
+            })
+            .collect();
+
+        Ok(ImportPlan { domain_id, records })
+    }
+
+    /// Adds the records of a plan from [`preview_zone_import`](DomeneshopClient::preview_zone_import) using
+    /// [`add_dns_record`](DomeneshopClient::add_dns_record).
+    ///
+    /// Records are added one by one, and a failing record does not stop the import.
+    /// The report contains the outcome of every record.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, plan), fields(domain_id = plan.domain_id))
+    )]
+    pub async fn import_zone(&self, plan: ImportPlan) -> ImportReport {
+        let mut results = Vec::with_capacity(plan.records.len());
+        for PlannedRecord { record, action } in plan.records {
+            let outcome = match action {
+                ImportAction::Create => {
+                    match self.add_dns_record(plan.domain_id, record.clone()).await {
+                        Ok(response) => ImportOutcome::Created(response.id),
+                        Err(err) => ImportOutcome::Failed(err),
+                    }
+                }
+                ImportAction::AlreadyExists(id) => ImportOutcome::AlreadyExists(id),
+                ImportAction::Invalid(violations) => ImportOutcome::Invalid(violations),
+            };
+            results.push(ImportResult { record, outcome });
+        }
+        ImportReport { results }
+    }
+}
@@ -0,0 +1,909 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use url::Host;
+
+use crate::endpoints::dns::{
+    AAAARecordData, ANAMERecordData, ARecordData, CAARecordData, CAATag, CNAMERecordData,
+    DSRecordData, DnsRecordData, MXRecordData, NSRecordData, SRVRecordData, TLSARecordData,
+    TXTRecordData, Ttl,
+};
+
+/// The records of a zone file, as returned by [`parse_zone`]
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ParsedZone {
+    /// Records that can be added through the API, in the order they appear in the file
+    pub records: Vec<DnsRecordData>,
+    /// Records that were skipped because the API does not support them
+    pub unsupported: Vec<UnsupportedRecord>,
+}
+
+/// A record in a zone file that cannot be added through the API
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct UnsupportedRecord {
+    /// Line of the file where the record starts
+    pub line: usize,
+    /// The host/subdomain of the record, relative to the origin
+    pub host: String,
+    /// The type of the record, e.g. `SOA`
+    pub dns_type: String,
+    /// Why the record is not supported
+    pub reason: String,
+}
+
+impl Display for UnsupportedRecord {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} {}: {}",
+            self.line, self.host, self.dns_type, self.reason
+        )
+    }
+}
+
+/// The error returned when a zone file cannot be parsed
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ZoneParseError {
+    /// Line of the file where the error was found
+    pub line: usize,
+    /// Description of the error
+    pub message: String,
+}
+
+impl ZoneParseError {
+    fn new(line: usize, message: impl Into<String>) -> ZoneParseError {
+        ZoneParseError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ZoneParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ZoneParseError {}
+
+/// Parses an RFC 1035 zone file, e.g. exported from BIND or another DNS provider, for the domain `origin`.
+///
+/// Supports `$ORIGIN`, `$TTL`, relative and absolute names, owners carried over from the previous record,
+/// TTLs with units like `1h`, quoted and multi-string TXT-data, parentheses spanning several lines and comments.
+/// Records without a TTL use `$TTL` or the TTL of the previous record.
+/// TTLs that are not valid for a [`Ttl`] are kept, so the record is reported by [`DnsRecordData::validate`]
+/// and [`preview_zone_import`](crate::client::DomeneshopClient::preview_zone_import) instead of failing the whole file.
+///
+/// Records the API does not support, like SOA, PTR and NS-records for the domain itself, are returned in
+/// [`ParsedZone::unsupported`] instead of failing the whole file. Hostnames in the data are returned without the trailing dot.
+/// Internationalized owner names and origins are converted to their ASCII form, so hosts like `blåbær` are returned as `xn--blbr-roah`.
+///
+/// # Example
+/// ```
+/// use domeneshop_client::zone::parse_zone;
+///
+/// let zone = parse_zone(
+///     r#"
+/// $TTL 1h
+/// @    IN SOA ns1.hyp.net. hostmaster.example.com. ( 2024010101 3h 1h 1w 1h )
+///      IN NS  ns1.hyp.net.
+/// www  IN A   192.0.2.1
+/// "#,
+///     "example.com",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(zone.records.len(), 1);
+/// assert_eq!(zone.records[0].host(), "www");
+/// assert_eq!(zone.unsupported[0].dns_type, "SOA");
+/// assert_eq!(zone.unsupported[1].dns_type, "NS");
+/// ```
+pub fn parse_zone(zone: &str, origin: &str) -> Result<ParsedZone, ZoneParseError> {
+    let zone_origin = ascii_name(&absolute_name(origin, "."));
+    let mut parser = Parser {
+        zone_origin: zone_origin.clone(),
+        origin: zone_origin,
+        default_ttl: None,
+        previous_ttl: None,
+        previous_owner: None,
+        parsed: ParsedZone::default(),
+    };
+    for entry in tokenize(zone)? {
+        parser.parse_entry(entry)?;
+    }
+    Ok(parser.parsed)
+}
+
+struct Parser {
+    zone_origin: String,
+    origin: String,
+    default_ttl: Option<u32>,
+    previous_ttl: Option<u32>,
+    previous_owner: Option<String>,
+    parsed: ParsedZone,
+}
+
+impl Parser {
+    fn parse_entry(&mut self, entry: Entry) -> Result<(), ZoneParseError> {
+        let line = entry.line;
+        let mut tokens = entry.tokens.into_iter().peekable();
+
+        let owner = if entry.inherits_owner {
+            self.previous_owner
+                .clone()
+                .ok_or_else(|| ZoneParseError::new(line, "Record has no owner name"))?
+        } else {
+            let first = tokens.next().expect("Entries have at least one token");
+            if !first.quoted && first.text.starts_with('$') {
+                return self.parse_directive(line, &first.text, tokens.collect());
+            }
+            absolute_name(&first.text, &self.origin)
+        };
+        self.previous_owner = Some(owner.clone());
+
+        let mut ttl = None;
+        while let Some(token) =
+            tokens.next_if(|token| !token.quoted && is_ttl_or_class(&token.text))
+        {
+            if token.text.eq_ignore_ascii_case("IN") {
+                continue;
+            }
+            if token.text.starts_with(|c: char| c.is_ascii_digit()) {
+                ttl = Some(parse_ttl(line, &token.text)?);
+            } else {
+                return Err(ZoneParseError::new(
+                    line,
+                    format!("Class {} is not supported, only IN", token.text),
+                ));
+            }
+        }
+        let dns_type = tokens
+            .next()
+            .ok_or_else(|| ZoneParseError::new(line, "Record has no type"))?
+            .text
+            .to_ascii_uppercase();
+        let rdata: Vec<Token> = tokens.collect();
+
+        let ttl = ttl.or(self.default_ttl).or(self.previous_ttl);
+        self.previous_ttl = ttl;
+
+        let host = self.relative_host(line, &owner)?;
+        if let Some(reason) = unsupported_reason(&host, &dns_type) {
+            self.parsed.unsupported.push(UnsupportedRecord {
+                line,
+                host,
+                dns_type,
+                reason: reason.to_string(),
+            });
+            return Ok(());
+        }
+        let seconds =
+            ttl.ok_or_else(|| ZoneParseError::new(line, "Record has no TTL and there is no $TTL"))?;
+        let ttl = Ttl::unchecked(seconds);
+        let record = self.parse_record(line, host, ttl, &dns_type, rdata)?;
+        self.parsed.records.push(record);
+        Ok(())
+    }
+
+    fn parse_directive(
+        &mut self,
+        line: usize,
+        directive: &str,
+        arguments: Vec<Token>,
+    ) -> Result<(), ZoneParseError> {
+        let directive = directive.to_ascii_uppercase();
+        if directive != "$ORIGIN" && directive != "$TTL" {
+            return Err(ZoneParseError::new(
+                line,
+                format!("Directive {} is not supported", directive),
+            ));
+        }
+        let argument = match arguments.as_slice() {
+            [argument] => &argument.text,
+            _ => {
+                return Err(ZoneParseError::new(
+                    line,
+                    format!("{} takes exactly one argument", directive),
+                ))
+            }
+        };
+        if directive == "$ORIGIN" {
+            self.origin = absolute_name(argument, &self.origin);
+        } else {
+            self.default_ttl = Some(parse_ttl(line, argument)?);
+        }
+        Ok(())
+    }
+
+    fn parse_record(
+        &self,
+        line: usize,
+        host: String,
+        ttl: Ttl,
+        dns_type: &str,
+        rdata: Vec<Token>,
+    ) -> Result<DnsRecordData, ZoneParseError> {
+        let mut rdata = Rdata {
+            line,
+            dns_type,
+            tokens: rdata.into_iter(),
+        };
+        let record = match dns_type {
+            "A" => DnsRecordData::A(ARecordData {
+                host,
+                ttl,
+                data: rdata.parse("address")?,
+            }),
+            "AAAA" => DnsRecordData::AAAA(AAAARecordData {
+                host,
+                ttl,
+                data: rdata.parse("address")?,
+            }),
+            "CNAME" => DnsRecordData::CNAME(CNAMERecordData {
+                host,
+                ttl,
+                data: self.target(rdata.next("target")?),
+            }),
+            "ANAME" | "ALIAS" => DnsRecordData::ANAME(ANAMERecordData {
+                host,
+                ttl,
+                data: self.target(rdata.next("target")?),
+            }),
+            "NS" => DnsRecordData::NS(NSRecordData {
+                host,
+                ttl,
+                data: self.target(rdata.next("name server")?),
+            }),
+            "MX" => DnsRecordData::MX(MXRecordData {
+                host,
+                ttl,
+                priority: rdata.parse("priority")?,
+                data: self.target(rdata.next("exchange")?),
+            }),
+            "SRV" => DnsRecordData::SRV(SRVRecordData {
+                host,
+                ttl,
+                priority: rdata.parse("priority")?,
+                weight: rdata.parse("weight")?,
+                port: rdata.parse("port")?,
+                data: self.target(rdata.next("target")?),
+            }),
+            "TXT" | "SPF" => DnsRecordData::TXT(TXTRecordData {
+                host,
+                ttl,
+                data: txt_data(rdata.rest("text")?),
+            }),
+            "CAA" => DnsRecordData::CAA(CAARecordData {
+                host,
+                ttl,
+                flags: rdata.parse("flags")?,
+                tag: parse_caa_tag(line, &rdata.next("tag")?)?,
+                data: rdata.next("value")?,
+            }),
+            "DS" => DnsRecordData::DS(DSRecordData {
+                host,
+                ttl,
+                tag: rdata.parse("key tag")?,
+                alg: rdata.parse("algorithm")?,
+                digest: rdata.parse("digest type")?,
+                data: rdata.rest("digest")?.concat(),
+            }),
+            "TLSA" => DnsRecordData::TLSA(TLSARecordData {
+                host,
+                ttl,
+                usage: rdata.parse("certificate usage")?,
+                selector: rdata.parse("selector")?,
+                dtype: rdata.parse("matching type")?,
+                data: rdata.rest("certificate association data")?.concat(),
+            }),
+            _ => {
+                return Err(ZoneParseError::new(
+                    line,
+                    format!("Record type {} is not supported", dns_type),
+                ))
+            }
+        };
+        rdata.finish()?;
+        Ok(record)
+    }
+
+    /// The host relative to the zone, `@` for the zone itself. Internationalized names are returned in their ASCII form
+    fn relative_host(&self, line: usize, owner: &str) -> Result<String, ZoneParseError> {
+        let name = ascii_name(owner);
+        if name.eq_ignore_ascii_case(&self.zone_origin) {
+            return Ok("@".to_string());
+        }
+        let suffix_start = name.len().saturating_sub(self.zone_origin.len() + 1);
+        match name.get(suffix_start..) {
+            Some(suffix)
+                if suffix.starts_with('.')
+                    && suffix[1..].eq_ignore_ascii_case(&self.zone_origin) =>
+            {
+                Ok(name[..suffix_start].to_string())
+            }
+            _ => Err(ZoneParseError::new(
+                line,
+                format!("Name {} is outside of the zone {}", owner, self.zone_origin),
+            )),
+        }
+    }
+
+    /// Hostnames in the data are sent fully qualified without the trailing dot. A single dot is kept as is
+    fn target(&self, name: String) -> String {
+        if name == "." {
+            return name;
+        }
+        absolute_name(&name, &self.origin)
+            .trim_end_matches('.')
+            .to_string()
+    }
+}
+
+fn unsupported_reason(host: &str, dns_type: &str) -> Option<&'static str> {
+    match dns_type {
+        "A" | "AAAA" | "CNAME" | "ANAME" | "ALIAS" | "MX" | "SRV" | "TXT" | "SPF" | "CAA"
+        | "DS" | "TLSA" => None,
+        "NS" if host != "@" => None,
+        "NS" => Some("Name servers of the domain itself are managed by Domeneshop"),
+        "SOA" => Some("The SOA-record is managed by Domeneshop"),
+        "PTR" => Some("Reverse DNS is managed by the owner of the IP address, not the domain"),
+        _ => Some("The record type is not supported by the API"),
+    }
+}
+
+/// Resolves `@` and relative names against `origin`. Returns the name with a trailing dot
+fn absolute_name(name: &str, origin: &str) -> String {
+    if name == "@" {
+        origin.to_string()
+    } else if name.ends_with('.') {
+        name.to_string()
+    } else if origin == "." {
+        format!("{}.", name)
+    } else {
+        format!("{}.{}", name, origin)
+    }
+}
+
+/// Converts internationalized labels to their ASCII form, as rendered by [`render_zone`](crate::zone::render_zone)
+fn ascii_name(name: &str) -> String {
+    if name.is_ascii() {
+        return name.to_string();
+    }
+    name.split('.')
+        .map(|label| match Host::parse(label) {
+            Ok(Host::Domain(ascii)) if !label.is_ascii() && !ascii.contains('.') => ascii,
+            _ => label.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn is_ttl_or_class(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit())
+        || ["IN", "CH", "HS", "CS"]
+            .iter()
+            .any(|class| token.eq_ignore_ascii_case(class))
+}
+
+/// Parses a TTL in seconds, or with units like `1h30m`
+fn parse_ttl(line: usize, ttl: &str) -> Result<u32, ZoneParseError> {
+    let invalid = || ZoneParseError::new(line, format!("Invalid TTL {}", ttl));
+    if let Ok(seconds) = ttl.parse() {
+        return Ok(seconds);
+    }
+    let mut seconds: u32 = 0;
+    let mut number = String::new();
+    for c in ttl.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return Err(invalid()),
+        };
+        let value: u32 = number.parse().map_err(|_| invalid())?;
+        seconds = value
+            .checked_mul(unit)
+            .and_then(|value| seconds.checked_add(value))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        return Err(invalid());
+    }
+    Ok(seconds)
+}
+
+fn parse_caa_tag(line: usize, tag: &str) -> Result<CAATag, ZoneParseError> {
+    match tag.to_ascii_lowercase().as_str() {
+        "issue" => Ok(CAATag::Issue),
+        "issuewild" => Ok(CAATag::IssueWild),
+        "iodef" => Ok(CAATag::Iodef),
+        _ => Err(ZoneParseError::new(
+            line,
+            format!("CAA tag {} is not supported", tag),
+        )),
+    }
+}
+
+/// A single string is sent as is, while several strings, or strings that need quoting, are sent quoted
+fn txt_data(strings: Vec<String>) -> String {
+    match strings.as_slice() {
+        [string] if !string.is_empty() && string.len() <= 255 && !string.contains('"') => {
+            string.clone()
+        }
+        _ => strings
+            .iter()
+            .map(|string| format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+struct Rdata<'a> {
+    line: usize,
+    dns_type: &'a str,
+    tokens: std::vec::IntoIter<Token>,
+}
+
+impl Rdata<'_> {
+    fn next(&mut self, field: &str) -> Result<String, ZoneParseError> {
+        self.tokens.next().map(|token| token.text).ok_or_else(|| {
+            ZoneParseError::new(
+                self.line,
+                format!("{}-record is missing {}", self.dns_type, field),
+            )
+        })
+    }
+
+    fn parse<T: FromStr>(&mut self, field: &str) -> Result<T, ZoneParseError> {
+        let value = self.next(field)?;
+        value.parse().map_err(|_| {
+            ZoneParseError::new(
+                self.line,
+                format!("Invalid {} {} in {}-record", field, value, self.dns_type),
+            )
+        })
+    }
+
+    fn rest(&mut self, field: &str) -> Result<Vec<String>, ZoneParseError> {
+        let rest: Vec<String> = self.tokens.by_ref().map(|token| token.text).collect();
+        if rest.is_empty() {
+            return Err(ZoneParseError::new(
+                self.line,
+                format!("{}-record is missing {}", self.dns_type, field),
+            ));
+        }
+        Ok(rest)
+    }
+
+    fn finish(mut self) -> Result<(), ZoneParseError> {
+        match self.tokens.next() {
+            Some(token) => Err(ZoneParseError::new(
+                self.line,
+                format!("Unexpected {} in {}-record", token.text, self.dns_type),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A record or directive, possibly spanning several lines within parentheses
+struct Entry {
+    line: usize,
+    /// Entries starting with whitespace have the owner of the previous record
+    inherits_owner: bool,
+    tokens: Vec<Token>,
+}
+
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+fn tokenize(zone: &str) -> Result<Vec<Entry>, ZoneParseError> {
+    let mut entries = Vec::new();
+    let mut chars = zone.chars().peekable();
+    let mut line = 1;
+    let mut depth = 0;
+    let mut entry = Entry {
+        line,
+        inherits_owner: false,
+        tokens: Vec::new(),
+    };
+    let mut at_line_start = true;
+
+    while let Some(&c) = chars.peek() {
+        if at_line_start && depth == 0 {
+            if !entry.tokens.is_empty() {
+                entries.push(entry);
+            }
+            entry = Entry {
+                line,
+                inherits_owner: c == ' ' || c == '\t',
+                tokens: Vec::new(),
+            };
+        }
+        at_line_start = false;
+        match c {
+            '\n' => {
+                chars.next();
+                line += 1;
+                at_line_start = true;
+            }
+            ';' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '(' => {
+                chars.next();
+                depth += 1;
+            }
+            ')' => {
+                chars.next();
+                if depth == 0 {
+                    return Err(ZoneParseError::new(line, "Unexpected )"));
+                }
+                depth -= 1;
+            }
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let start = line;
+                let mut text = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => unescape(&mut chars, &mut text, line)?,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            push_char(&mut text, c);
+                        }
+                        None => {
+                            return Err(ZoneParseError::new(start, "Unterminated quoted string"))
+                        }
+                    }
+                }
+                entry.tokens.push(Token {
+                    text: String::from_utf8_lossy(&text).into_owned(),
+                    quoted: true,
+                });
+            }
+            _ => {
+                let mut text = Vec::new();
+                while let Some(c) =
+                    chars.next_if(|&c| !c.is_whitespace() && !matches!(c, ';' | '(' | ')' | '"'))
+                {
+                    if c == '\\' {
+                        unescape(&mut chars, &mut text, line)?;
+                    } else {
+                        push_char(&mut text, c);
+                    }
+                }
+                entry.tokens.push(Token {
+                    text: String::from_utf8_lossy(&text).into_owned(),
+                    quoted: false,
+                });
+            }
+        }
+    }
+    if depth > 0 {
+        return Err(ZoneParseError::new(entry.line, "Unclosed ("));
+    }
+    if !entry.tokens.is_empty() {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+fn push_char(text: &mut Vec<u8>, c: char) {
+    let mut buffer = [0; 4];
+    text.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+}
+
+/// Decodes `\X` as `X` and `\DDD` as the byte with the decimal value `DDD`
+fn unescape(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    text: &mut Vec<u8>,
+    line: usize,
+) -> Result<(), ZoneParseError> {
+    let invalid = || ZoneParseError::new(line, "Invalid escape sequence");
+    match chars.next() {
+        Some(c) if c.is_ascii_digit() => {
+            let mut digits = c.to_string();
+            for _ in 0..2 {
+                digits.push(chars.next_if(char::is_ascii_digit).ok_or_else(invalid)?);
+            }
+            text.push(digits.parse().map_err(|_| invalid())?);
+        }
+        Some(c) => push_char(text, c),
+        None => return Err(invalid()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::dns::{
+        ARecordData, CAARecordData, CAATag, DnsRecordData, MXRecordData, RecordField,
+        SRVRecordData, TXTRecordData, Ttl,
+    };
+    use crate::zone::render_zone;
+
+    use super::{parse_ttl, parse_zone};
+
+    #[test]
+    fn names_are_resolved_against_origin() {
+        let zone = parse_zone(
+            "$TTL 300\n\
+             @                IN A     192.0.2.1\n\
+             www.example.com. IN A     192.0.2.2\n\
+             $ORIGIN dev.example.com.\n\
+             api              IN CNAME @\n\
+             @                IN MX    10 mail\n\
+             \x20                IN MX    20 mail.example.net.\n",
+            "example.com",
+        )
+        .unwrap();
+
+        let hosts: Vec<_> = zone.records.iter().map(|record| record.host()).collect();
+        let data: Vec<_> = zone.records.iter().map(|record| record.data()).collect();
+        assert_eq!(hosts, vec!["@", "www", "api.dev", "dev", "dev"]);
+        assert_eq!(
+            data,
+            vec![
+                "192.0.2.1",
+                "192.0.2.2",
+                "dev.example.com",
+                "mail.dev.example.com",
+                "mail.example.net"
+            ]
+        );
+    }
+
+    #[test]
+    fn ttl_falls_back_to_directive_and_previous_record() {
+        let zone = parse_zone(
+            "a 1h IN A 192.0.2.1\n\
+             b IN A 192.0.2.2\n\
+             $TTL 1d\n\
+             c IN 600 A 192.0.2.3\n\
+             d A 192.0.2.4\n",
+            "example.com",
+        )
+        .unwrap();
+
+        let ttls: Vec<_> = zone
+            .records
+            .iter()
+            .map(|record| record.ttl().seconds())
+            .collect();
+        assert_eq!(ttls, vec![3600, 3600, 600, 86_400]);
+    }
+
+    #[test]
+    fn parentheses_comments_and_quotes_are_handled() {
+        let zone = parse_zone(
+            "$TTL 3600 ; default\n\
+             _sip._tcp IN SRV ( 10 ; priority\n\
+             \x20    5 5060 ; weight and port\n\
+             \x20    sip ) ; target\n\
+             txt IN TXT \"v=DKIM1; k=rsa\" \"p=abc\" ; two strings\n\
+             one IN TXT \"say \\\"hi\\\"; \\065\"\n\
+             plain IN TXT hello\n\
+             @ IN CAA 0 issue \"letsencrypt.org\"\n",
+            "example.com",
+        )
+        .unwrap();
+
+        assert_eq!(
+            zone.records,
+            vec![
+                DnsRecordData::SRV(SRVRecordData {
+                    host: "_sip._tcp".to_string(),
+                    ttl: Ttl::HOUR,
+                    data: "sip.example.com".to_string(),
+                    priority: 10,
+                    weight: 5,
+                    port: 5060,
+                }),
+                DnsRecordData::TXT(TXTRecordData {
+                    host: "txt".to_string(),
+                    ttl: Ttl::HOUR,
+                    data: "\"v=DKIM1; k=rsa\" \"p=abc\"".to_string(),
+                }),
+                DnsRecordData::TXT(TXTRecordData {
+                    host: "one".to_string(),
+                    ttl: Ttl::HOUR,
+                    data: "\"say \\\"hi\\\"; A\"".to_string(),
+                }),
+                DnsRecordData::TXT(TXTRecordData {
+                    host: "plain".to_string(),
+                    ttl: Ttl::HOUR,
+                    data: "hello".to_string(),
+                }),
+                DnsRecordData::CAA(CAARecordData {
+                    host: "@".to_string(),
+                    ttl: Ttl::HOUR,
+                    data: "letsencrypt.org".to_string(),
+                    flags: 0,
+                    tag: CAATag::Issue,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_records_are_reported() {
+        let zone = parse_zone(
+            "$TTL 3600\n\
+             @ IN SOA ns1 hostmaster (\n\
+             \x20   1 7200 3600 1209600 3600 )\n\
+             \x20 IN NS ns1.hyp.net.\n\
+             sub IN NS ns1.example.net.\n\
+             1 IN PTR host.example.com.\n\
+             @ IN HINFO \"cpu\" \"os\"\n",
+            "example.com",
+        )
+        .unwrap();
+
+        assert_eq!(zone.records.len(), 1);
+        assert_eq!(zone.records[0].host(), "sub");
+        let unsupported: Vec<_> = zone
+            .unsupported
+            .iter()
+            .map(|record| (record.line, record.host.as_str(), record.dns_type.as_str()))
+            .collect();
+        assert_eq!(
+            unsupported,
+            vec![
+                (2, "@", "SOA"),
+                (4, "@", "NS"),
+                (6, "1", "PTR"),
+                (7, "@", "HINFO")
+            ]
+        );
+        assert_eq!(
+            zone.unsupported[0].to_string(),
+            "line 2: @ SOA: The SOA-record is managed by Domeneshop"
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let cases = [
+            (
+                "www IN A 192.0.2.1\n",
+                "Record has no TTL and there is no $TTL",
+            ),
+            (
+                "$TTL 3600\n\nwww IN A not-an-ip\n",
+                "Invalid address not-an-ip in A-record",
+            ),
+            ("$TTL 3600\nwww IN MX 10\n", "MX-record is missing exchange"),
+            (
+                "$TTL 3600\nwww IN A 192.0.2.1 extra\n",
+                "Unexpected extra in A-record",
+            ),
+            (
+                "$TTL 3600\nwww.example.net. IN A 192.0.2.1\n",
+                "Name www.example.net. is outside of the zone example.com.",
+            ),
+            (
+                "$TTL 3600\nwww CH A 192.0.2.1\n",
+                "Class CH is not supported, only IN",
+            ),
+            (
+                "$INCLUDE other.zone\n",
+                "Directive $INCLUDE is not supported",
+            ),
+            (
+                "$TTL 3600\nwww IN TXT \"open\n",
+                "Unterminated quoted string",
+            ),
+            ("$TTL 3600\nwww IN SRV ( 1 2 3\n", "Unclosed ("),
+        ];
+        for (zone, message) in cases {
+            let err = parse_zone(zone, "example.com").unwrap_err();
+            assert_eq!(err.message, message, "{}", zone);
+        }
+        assert_eq!(
+            parse_zone("$TTL 3600\n\nwww IN A x\n", "example.com")
+                .unwrap_err()
+                .line,
+            3
+        );
+    }
+
+    #[test]
+    fn invalid_ttls_are_kept_for_validation() {
+        let zone = parse_zone(
+            "www 90 IN A 192.0.2.1\nmail 30 IN A 192.0.2.2\napi 1h IN A 192.0.2.3\n",
+            "example.com",
+        )
+        .unwrap();
+
+        let invalid: Vec<_> = zone
+            .records
+            .iter()
+            .map(|record| {
+                record
+                    .validate()
+                    .iter()
+                    .any(|v| v.field == RecordField::Ttl)
+            })
+            .collect();
+        assert_eq!(invalid, vec![true, true, false]);
+        assert_eq!(zone.records[0].ttl().seconds(), 90);
+    }
+
+    #[test]
+    fn ttl_units_are_parsed() {
+        assert_eq!(parse_ttl(1, "300").unwrap(), 300);
+        assert_eq!(parse_ttl(1, "1h30m").unwrap(), 5400);
+        assert_eq!(parse_ttl(1, "1W").unwrap(), 604_800);
+        assert!(parse_ttl(1, "1x").is_err());
+        assert!(parse_ttl(1, "h").is_err());
+        assert!(parse_ttl(1, "1h5").is_err());
+    }
+
+    #[test]
+    fn rendered_zone_is_parsed_back() {
+        let records = vec![
+            DnsRecordData::A(ARecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "192.0.2.1".parse().unwrap(),
+            }),
+            DnsRecordData::MX(MXRecordData {
+                host: "@".to_string(),
+                ttl: Ttl::DAY,
+                data: "mail.example.com".to_string(),
+                priority: 10,
+            }),
+            DnsRecordData::TXT(TXTRecordData {
+                host: "_dmarc".to_string(),
+                ttl: Ttl::HOUR,
+                data: "v=DMARC1; p=none".to_string(),
+            }),
+            DnsRecordData::TXT(TXTRecordData {
+                host: "long".to_string(),
+                ttl: Ttl::HOUR,
+                data: "\"first\" \"say \\\"hi\\\"\"".to_string(),
+            }),
+        ];
+
+        let mut expected = records.clone();
+        expected.sort_by_key(|record| record.host().to_string());
+        for origin in ["example.com", "blåbær.no"] {
+            let zone = parse_zone(&render_zone(origin, &records), origin).unwrap();
+
+            let mut parsed = zone.records;
+            parsed.sort_by_key(|record| record.host().to_string());
+            assert_eq!(parsed, expected, "{}", origin);
+            assert!(zone.unsupported.is_empty());
+        }
+    }
+
+    #[test]
+    fn internationalized_names_are_converted_to_ascii() {
+        let zone = parse_zone(
+            "$TTL 3600\nsmørbrød.blåbær.no. IN A 192.0.2.1\nwww.xn--blbr-roah.no. IN A 192.0.2.2\n",
+            "blåbær.no",
+        )
+        .unwrap();
+
+        let hosts: Vec<_> = zone.records.iter().map(|record| record.host()).collect();
+        assert_eq!(hosts, vec!["xn--smrbrd-cyad", "www"]);
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use domeneshop_client::{
+    audit::{AuditEndpoint, AuditEvent, AuditLog, AuditOutcome, AuditSink, JsonLinesSink},
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    dry_run::DryRun,
+    endpoints::{
+        dns::{DnsRecordData, ExistingDnsRecord, TXTRecordData, Ttl},
+        forwards::HttpForward,
+    },
+    errors::DomeneshopError,
+    http_client::fake::{self, FakeDomeneshop},
+    retry::RetryPolicy,
+};
+use url::Url;
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+#[derive(Clone, Default)]
+struct CollectingSink(Arc<Mutex<Vec<AuditEvent>>>);
+
+impl AuditSink for CollectingSink {
+    fn record(&self, event: &AuditEvent) -> Result<(), DomeneshopError> {
+        self.0.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+impl CollectingSink {
+    fn events(&self) -> Vec<AuditEvent> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+fn create_client(
+    fake: &FakeDomeneshop,
+    audit: AuditLog,
+    dry_run: Option<DryRun>,
+) -> DomeneshopClient<FakeDomeneshop> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        fake.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy::disabled(),
+            audit: Some(audit),
+            dry_run,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn create_fake() -> FakeDomeneshop {
+    let fake = FakeDomeneshop::new();
+    fake.add_domain(fake::domain(1, "example.com"));
+    fake
+}
+
+fn txt_record(data: &str) -> DnsRecordData {
+    DnsRecordData::TXT(TXTRecordData {
+        host: "@".to_string(),
+        ttl: Ttl::HOUR,
+        data: data.to_string(),
+    })
+}
+
+#[tokio::test]
+async fn dns_mutations_are_audited() {
+    let fake = create_fake();
+    let sink = CollectingSink::default();
+    let client = create_client(
+        &fake,
+        AuditLog::new(sink.clone())
+            .actor("alice")
+            .capture_before(true),
+        None,
+    );
+
+    let id = client
+        .add_dns_record(1, txt_record("first"))
+        .await
+        .unwrap()
+        .id;
+    client
+        .update_dns_record(
+            1,
+            ExistingDnsRecord {
+                id,
+                data: txt_record("second"),
+            },
+        )
+        .await
+        .unwrap();
+    client.delete_dns_record(1, id).await.unwrap();
+    client.list_dns_records(1).await.unwrap();
+
+    let events = sink.events();
+    let endpoints: Vec<_> = events.iter().map(|event| event.endpoint).collect();
+    assert_eq!(
+        endpoints,
+        vec![
+            AuditEndpoint::AddDnsRecord,
+            AuditEndpoint::UpdateDnsRecord,
+            AuditEndpoint::DeleteDnsRecord
+        ]
+    );
+    for event in &events {
+        assert_eq!(event.actor.as_deref(), Some("alice"));
+        assert_eq!(event.domain_id, Some(1));
+        assert_eq!(event.record_id, Some(id));
+        assert_eq!(event.outcome, AuditOutcome::Success);
+    }
+    assert_eq!(events[0].before, None);
+    assert_eq!(events[0].after.as_ref().unwrap()["data"], "first");
+    assert_eq!(events[1].before.as_ref().unwrap()["data"], "first");
+    assert_eq!(events[1].after.as_ref().unwrap()["data"], "second");
+    assert_eq!(events[2].before.as_ref().unwrap()["data"], "second");
+    assert_eq!(events[2].after, None);
+}
+
+#[tokio::test]
+async fn failures_are_audited() {
+    let fake = create_fake();
+    let sink = CollectingSink::default();
+    let client = create_client(&fake, AuditLog::new(sink.clone()), None);
+
+    client
+        .delete_forward(1, "www".to_string())
+        .await
+        .unwrap_err();
+
+    let events = sink.events();
+    assert_eq!(events[0].endpoint, AuditEndpoint::DeleteForward);
+    assert_eq!(events[0].forward_host.as_deref(), Some("www"));
+    assert!(matches!(
+        events[0].outcome,
+        AuditOutcome::Failure {
+            status_code: Some(404),
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn dry_run_outcome_is_audited() {
+    let fake = create_fake();
+    let sink = CollectingSink::default();
+    let client = create_client(&fake, AuditLog::new(sink.clone()), Some(DryRun::new()));
+    let forward = HttpForward {
+        host: "www".to_string(),
+        frame: false,
+        url: Url::parse("https://example.org").unwrap(),
+    };
+
+    client.add_forward(1, forward).await.unwrap();
+    client
+        .update_dyndns("home.example.com", Some(IpAddr::from([10, 0, 0, 1])))
+        .await
+        .unwrap();
+
+    let events = sink.events();
+    assert_eq!(events[0].forward_host.as_deref(), Some("www"));
+    assert_eq!(events[0].outcome, AuditOutcome::DryRun);
+    assert_eq!(events[1].endpoint, AuditEndpoint::UpdateDyndns);
+    assert_eq!(events[1].hostname.as_deref(), Some("home.example.com"));
+    assert_eq!(events[1].after.as_ref().unwrap()["myip"], "10.0.0.1");
+}
+
+#[tokio::test]
+async fn sink_errors_are_counted_and_do_not_fail_the_call() {
+    struct FailingSink;
+
+    impl AuditSink for FailingSink {
+        fn record(&self, _: &AuditEvent) -> Result<(), DomeneshopError> {
+            Err(DomeneshopError::new("Disk full"))
+        }
+    }
+
+    let fake = create_fake();
+    let audit = AuditLog::new(FailingSink);
+    let client = create_client(&fake, audit.clone(), None);
+
+    client.add_dns_record(1, txt_record("first")).await.unwrap();
+    client
+        .add_dns_record(1, txt_record("second"))
+        .await
+        .unwrap();
+
+    assert_eq!(audit.failed_events(), 2);
+    assert_eq!(fake.dns_records(1).len(), 2);
+}
+
+#[tokio::test]
+async fn json_lines_sink_appends_events() {
+    let path = std::env::temp_dir().join(format!(
+        "domeneshop_client_audit_{}.jsonl",
+        std::process::id()
+    ));
+    _ = std::fs::remove_file(&path);
+    let fake = create_fake();
+    let client = create_client(
+        &fake,
+        AuditLog::new(JsonLinesSink::new(&path).unwrap()).actor("script"),
+        None,
+    );
+
+    client.add_dns_record(1, txt_record("first")).await.unwrap();
+    client
+        .add_dns_record(1, txt_record("second"))
+        .await
+        .unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let lines: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["actor"], "script");
+    assert_eq!(lines[1]["endpoint"], "add_dns_record");
+    assert_eq!(lines[1]["after"]["data"], "second");
+    assert_eq!(lines[1]["outcome"]["status"], "success");
+    assert!(lines[1]["timestamp"].is_string());
+}
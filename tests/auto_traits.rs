@@ -0,0 +1,109 @@
+//! Compile-time checks of the auto traits of the client and the futures it returns.
+//! The futures are never polled; the tests fail to compile if a bound is not met.
+use domeneshop_client::{
+    blocking,
+    cache::ResponseCache,
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::{
+        dns::{CNAMERecordData, DnsRecordData, DnsType, ExistingDnsRecord, Ttl},
+        forwards::HttpForward,
+        invoices::InvoiceStatus,
+    },
+    errors::DomeneshopError,
+    http::BoxedHttpClient,
+    http_client::{fake::FakeDomeneshop, mock::ClosureMockClient},
+    layer::Layer,
+    rate_limit::RateLimiter,
+};
+use url::Url;
+
+fn assert_send<T: Send>(_: &T) {}
+fn assert_send_sync<T: Send + Sync>() {}
+fn assert_clone<T: Clone>() {}
+
+fn create_client() -> DomeneshopClient<BoxedHttpClient> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        BoxedHttpClient::new(FakeDomeneshop::new()),
+        DomeneshopClientConfiguration::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn client_is_send_sync_and_clone() {
+    assert_send_sync::<DomeneshopClient>();
+    assert_clone::<DomeneshopClient>();
+    assert_send_sync::<DomeneshopClient<FakeDomeneshop>>();
+    assert_clone::<DomeneshopClient<FakeDomeneshop>>();
+    assert_send_sync::<blocking::DomeneshopClient>();
+    assert_clone::<blocking::DomeneshopClient>();
+}
+
+#[test]
+fn shared_types_are_send_sync() {
+    assert_send_sync::<DomeneshopError>();
+    assert_send_sync::<Credentials>();
+    assert_send_sync::<RateLimiter>();
+    assert_send_sync::<ResponseCache>();
+    assert_send_sync::<BoxedHttpClient>();
+    assert_send_sync::<Box<dyn Layer>>();
+    assert_send_sync::<ClosureMockClient>();
+}
+
+#[test]
+fn futures_are_send() {
+    let client = create_client();
+    let record = DnsRecordData::CNAME(CNAMERecordData {
+        host: "www".to_string(),
+        ttl: Ttl::HOUR,
+        data: "example.com".to_string(),
+    });
+    let forward = HttpForward {
+        host: "www".to_string(),
+        frame: false,
+        url: Url::parse("https://example.com").unwrap(),
+    };
+
+    assert_send(&client.list_domains());
+    assert_send(&client.list_domains_with_filter(".no"));
+    assert_send(&client.get_domain(1));
+    assert_send(&client.list_dns_records(1));
+    assert_send(&client.list_dns_records_with_filter(1, None, Some(DnsType::A)));
+    assert_send(&client.get_dns_record(1, 1));
+    assert_send(&client.add_dns_record(1, record.clone()));
+    assert_send(&client.update_dns_record(
+        1,
+        ExistingDnsRecord {
+            id: 1,
+            data: record,
+        },
+    ));
+    assert_send(&client.delete_dns_record(1, 1));
+    assert_send(&client.list_forwards(1));
+    assert_send(&client.get_forward(1, "www".to_string()));
+    assert_send(&client.add_forward(1, forward.clone()));
+    assert_send(&client.update_forward(1, forward));
+    assert_send(&client.delete_forward(1, "www".to_string()));
+    assert_send(&client.list_invoices());
+    assert_send(&client.list_invoices_with_status(InvoiceStatus::Paid));
+    assert_send(&client.get_invoice(1));
+    assert_send(&client.update_dyndns("www.example.com", None));
+}
+
+#[tokio::test]
+async fn client_can_be_shared_between_tasks() {
+    let client = create_client();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.list_domains().await })
+        })
+        .collect();
+
+    for handle in handles {
+        assert!(handle.await.unwrap().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use domeneshop_client::{
+    blocking::{DomeneshopClient, DomeneshopClientConfiguration},
+    endpoints::dns::{CNAMERecordData, DnsRecordData, Ttl},
+    errors::{DomeneshopError, DomeneshopErrorKind},
+    http_client::mock::BlockingMockClient,
+    retry::RetryPolicy,
+};
+use http_types::{Method, Request, Response, StatusCode};
+
+use crate::common::{assert_url_equal, TEST_BASE_URL};
+mod common;
+
+fn create_client(underlying_client: BlockingMockClient) -> DomeneshopClient {
+    DomeneshopClient::new(
+        String::from("token"),
+        String::from("secret"),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            },
+            underlying_client: Some(Box::new(underlying_client)),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn list_domains_with_filter_adds_correct_query_parameter() {
+    fn receive_request(req: Request) -> Result<Response, DomeneshopError> {
+        assert_url_equal(req.url(), "/domains?domain=.no");
+        assert_eq!(
+            req.header("Authorization").unwrap(),
+            "Basic dG9rZW46c2VjcmV0"
+        );
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(BlockingMockClient {
+        req_received: receive_request,
+    });
+
+    let domains = client.list_domains_with_filter(".no").unwrap();
+
+    assert_eq!(domains.len(), 0);
+}
+
+#[test]
+fn add_dns_record_returns_id() {
+    fn receive_request(req: Request) -> Result<Response, DomeneshopError> {
+        assert_url_equal(req.url(), "/domains/3/dns");
+        assert_eq!(req.method(), Method::Post);
+        let mut response = Response::new(StatusCode::Created);
+        response.set_body("{\"id\": 5}");
+        Ok(response)
+    }
+
+    let client = create_client(BlockingMockClient {
+        req_received: receive_request,
+    });
+
+    let record = DnsRecordData::CNAME(CNAMERecordData {
+        host: "t".to_string(),
+        ttl: Ttl::HOUR,
+        data: "a".to_string(),
+    });
+
+    let response = client.add_dns_record(3, record).unwrap();
+    assert_eq!(response.id, 5);
+}
+
+#[test]
+fn delete_forward_404_returns_err() {
+    fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        Ok(Response::new(StatusCode::NotFound))
+    }
+
+    let client = create_client(BlockingMockClient {
+        req_received: receive_request,
+    });
+
+    let error = client.delete_forward(3, "www".to_string()).unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &DomeneshopErrorKind::UnexpectedStatus(StatusCode::NotFound)
+    );
+}
+
+#[test]
+fn failed_requests_are_retried() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Ok(Response::new(StatusCode::BadGateway));
+        }
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(BlockingMockClient {
+        req_received: receive_request,
+    });
+
+    client.list_invoices().unwrap();
+
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
+
+#[test]
+fn request_deserializes_response() {
+    fn receive_request(req: Request) -> Result<Response, DomeneshopError> {
+        assert_url_equal(req.url(), "/domains/3");
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("{\"id\": 3}");
+        Ok(response)
+    }
+
+    let client = create_client(BlockingMockClient {
+        req_received: receive_request,
+    });
+
+    let response: serde_json::Value = client
+        .request::<(), _>(Method::Get, "/domains/3", &[], None)
+        .unwrap();
+
+    assert_eq!(response["id"], 3);
+}
@@ -0,0 +1,163 @@
+use std::{net::IpAddr, time::Duration};
+
+use domeneshop_client::{
+    cache::{CacheEndpoint, ResponseCache},
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::dns::{DnsRecordData, TXTRecordData, Ttl},
+    http_client::fake::{self, FakeDomeneshop},
+};
+use http_types::StatusCode;
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client(fake: &FakeDomeneshop, cache: &ResponseCache) -> DomeneshopClient<FakeDomeneshop> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        fake.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            cache: Some(cache.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn create_fake() -> FakeDomeneshop {
+    let fake = FakeDomeneshop::new();
+    fake.add_domain(fake::domain(1, "example.com"));
+    fake.add_domain(fake::domain(2, "example.no"));
+    fake
+}
+
+fn create_cache() -> ResponseCache {
+    ResponseCache::new()
+        .with_ttl(CacheEndpoint::Domains, Duration::from_secs(60))
+        .with_ttl(CacheEndpoint::DnsRecords, Duration::from_secs(60))
+}
+
+fn txt_record(data: &str) -> DnsRecordData {
+    DnsRecordData::TXT(TXTRecordData {
+        host: "@".to_string(),
+        ttl: Ttl::HOUR,
+        data: data.to_string(),
+    })
+}
+
+#[tokio::test]
+async fn responses_are_cached() {
+    let fake = create_fake();
+    let client = create_client(&fake, &create_cache());
+
+    let before = client.list_dns_records(1).await.unwrap();
+    fake.add_dns_record(1, txt_record("outside"));
+    let domains = client.list_domains().await.unwrap();
+    fake.add_domain(fake::domain(3, "example.org"));
+    let after = client.list_dns_records(1).await.unwrap();
+    let cached_domains = client.list_domains().await.unwrap();
+
+    assert_eq!(before, after);
+    assert_eq!(domains, cached_domains);
+}
+
+#[tokio::test]
+async fn endpoints_without_ttl_are_not_cached() {
+    let fake = create_fake();
+    let cache = ResponseCache::new().with_ttl(CacheEndpoint::Domains, Duration::from_secs(60));
+    let client = create_client(&fake, &cache);
+
+    client.list_dns_records(1).await.unwrap();
+    fake.add_dns_record(1, txt_record("outside"));
+
+    assert_eq!(client.list_dns_records(1).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn mutations_invalidate_the_domain() {
+    let fake = create_fake();
+    let client = create_client(&fake, &create_cache());
+
+    client.list_dns_records(1).await.unwrap();
+    client.list_dns_records(2).await.unwrap();
+    fake.add_dns_record(2, txt_record("outside"));
+    let id = client
+        .add_dns_record(1, txt_record("first"))
+        .await
+        .unwrap()
+        .id;
+
+    assert_eq!(client.list_dns_records(1).await.unwrap().len(), 1);
+    assert_eq!(client.list_dns_records(2).await.unwrap().len(), 0);
+
+    client.delete_dns_record(1, id).await.unwrap();
+
+    assert_eq!(client.list_dns_records(1).await.unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn dyndns_invalidates_dns_records() {
+    let fake = create_fake();
+    let client = create_client(&fake, &create_cache());
+
+    client.list_dns_records(1).await.unwrap();
+    client
+        .update_dyndns("home.example.com", Some(IpAddr::from([10, 0, 0, 1])))
+        .await
+        .unwrap();
+
+    assert_eq!(client.list_dns_records(1).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn invalidate_domain_removes_entries() {
+    let fake = create_fake();
+    let client = create_client(&fake, &create_cache());
+
+    client.list_dns_records(1).await.unwrap();
+    fake.add_dns_record(1, txt_record("outside"));
+    client.invalidate_domain(1);
+
+    assert_eq!(client.list_dns_records(1).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn cache_is_shared_between_clients() {
+    let fake = create_fake();
+    let cache = create_cache();
+    let first = create_client(&fake, &cache);
+    let second = create_client(&fake, &cache);
+
+    let (records, _) = tokio::join!(first.list_dns_records(1), second.list_dns_records(2));
+    records.unwrap();
+    fake.add_dns_record(1, txt_record("outside"));
+    let cached = second.list_dns_records(1).await.unwrap();
+    first.add_dns_record(1, txt_record("inside")).await.unwrap();
+    let invalidated = second.list_dns_records(1).await.unwrap();
+
+    assert_eq!(cached.len(), 0);
+    assert_eq!(invalidated.len(), 2);
+}
+
+#[tokio::test]
+async fn cache_is_not_shared_between_credentials() {
+    let fake = create_fake().with_credentials("token", "secret");
+    let cache = create_cache();
+    let client = create_client(&fake, &cache);
+    let other = DomeneshopClient::with_http_client(
+        Credentials::new("token", "other"),
+        fake.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            cache: Some(cache.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    client.list_domains().await.unwrap();
+    let error = other.list_domains().await.unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::Unauthorized));
+}
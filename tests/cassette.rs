@@ -0,0 +1,184 @@
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::dns::{CNAMERecordData, DnsRecordData, Ttl},
+    errors::DomeneshopError,
+    http::HttpClient,
+    http_client::{
+        cassette::{Cassette, RecordingClient, ReplayClient, SCRUBBED},
+        mock::MockClient,
+    },
+};
+use http_types::{Method, Request, Response, StatusCode};
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client<H: HttpClient>(http_client: H) -> DomeneshopClient<H> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        http_client,
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn cassette_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!(
+        "domeneshop-client-cassette-{}-{}.json",
+        name,
+        std::process::id()
+    ))
+}
+
+async fn route(req: Request) -> Result<Response, DomeneshopError> {
+    let mut response = match req.method() {
+        Method::Get => {
+            let mut response = Response::new(StatusCode::Ok);
+            response.set_body("[{\"id\": 1, \"host\": \"www\", \"ttl\": 3600, \"type\": \"CNAME\", \"data\": \"example.com\"}]");
+            response
+        }
+        Method::Post => {
+            let mut response = Response::new(StatusCode::Created);
+            response.set_body("{\"id\": 2}");
+            response
+        }
+        _ => Response::new(StatusCode::NoContent),
+    };
+    response.insert_header("X-Request-Id", "abc");
+    Ok(response)
+}
+
+fn record() -> DnsRecordData {
+    DnsRecordData::CNAME(CNAMERecordData {
+        host: "www".to_string(),
+        ttl: Ttl::HOUR,
+        data: "example.com".to_string(),
+    })
+}
+
+async fn record_session(name: &str) -> std::path::PathBuf {
+    let path = cassette_path(name);
+    let client = create_client(RecordingClient::new(
+        MockClient {
+            req_received: route,
+        },
+        &path,
+    ));
+
+    client.list_dns_records(1).await.unwrap();
+    client.add_dns_record(1, record()).await.unwrap();
+    client.delete_dns_record(1, 2).await.unwrap();
+    path
+}
+
+#[tokio::test]
+async fn recording_scrubs_authorization_header() {
+    let path = record_session("scrub").await;
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let cassette = Cassette::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(!content.contains("Basic dG9rZW46c2VjcmV0"));
+    assert_eq!(cassette.interactions.len(), 3);
+    for interaction in &cassette.interactions {
+        assert_eq!(interaction.request.headers["authorization"], vec![SCRUBBED]);
+    }
+    let add = &cassette.interactions[1];
+    assert_eq!(add.request.method, "POST");
+    assert_eq!(add.request.url, "https://test.local/v0/domains/1/dns");
+    assert!(add.request.body.contains("example.com"));
+    assert_eq!(add.response.status, 201);
+    assert_eq!(add.response.headers["x-request-id"], vec!["abc"]);
+}
+
+#[tokio::test]
+async fn replay_serves_recorded_responses() {
+    let path = record_session("replay").await;
+    let replay = ReplayClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let client = create_client(replay);
+
+    let records = client.list_dns_records(1).await.unwrap();
+    let added = client.add_dns_record(1, record()).await.unwrap();
+    client.delete_dns_record(1, 2).await.unwrap();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(added.id, 2);
+}
+
+#[tokio::test]
+#[should_panic(expected = "No interaction in the cassette matches DELETE")]
+async fn replay_panics_on_unmatched_request() {
+    let path = record_session("unmatched").await;
+    let replay = ReplayClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let client = create_client(replay);
+
+    _ = client.delete_dns_record(1, 3).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "was requested out of order")]
+async fn replay_panics_on_out_of_order_request() {
+    let path = record_session("out_of_order").await;
+    let replay = ReplayClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let client = create_client(replay);
+
+    _ = client.add_dns_record(1, record()).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "were never requested")]
+async fn replay_panics_on_unused_interactions() {
+    let path = record_session("unused").await;
+    let replay = ReplayClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let client = create_client(replay);
+
+    client.list_dns_records(1).await.unwrap();
+    drop(client);
+}
+
+#[tokio::test]
+async fn bodies_that_are_not_utf8_are_kept() {
+    const BODY: &[u8] = &[0x25, 0x50, 0x44, 0x46, 0xff, 0xfe, 0x00];
+    async fn binary(mut req: Request) -> Result<Response, DomeneshopError> {
+        assert_eq!(req.body_bytes().await.unwrap(), BODY);
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body(BODY);
+        Ok(response)
+    }
+    fn request() -> Request {
+        let mut request = Request::new(Method::Post, "https://test.local/v0/upload");
+        request.set_body(BODY);
+        request
+    }
+
+    let path = cassette_path("binary");
+    let recorder = RecordingClient::new(
+        MockClient {
+            req_received: binary,
+        },
+        &path,
+    );
+    let mut recorded = recorder.execute_request(request()).await.unwrap();
+    assert_eq!(recorded.body_bytes().await.unwrap(), BODY);
+
+    let cassette = Cassette::load(&path).unwrap();
+    let interaction = &cassette.interactions[0];
+    assert!(interaction.request.body_base64);
+    assert_eq!(interaction.request.body_bytes().unwrap(), BODY);
+    assert_eq!(interaction.response.body_bytes().unwrap(), BODY);
+
+    let replay = ReplayClient::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let mut replayed = replay.execute_request(request()).await.unwrap();
+
+    assert_eq!(replayed.body_bytes().await.unwrap(), BODY);
+}
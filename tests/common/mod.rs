@@ -1,5 +1,8 @@
+#![allow(dead_code)]
+
 use domeneshop_client::{
     client::{DomeneshopClient, DomeneshopClientConfiguration, API_VERSION},
+    credentials::Credentials,
     errors::DomeneshopError,
     http_client::mock::MockClient,
 };
@@ -8,17 +11,16 @@ use url::Url;
 
 pub const TEST_BASE_URL: &str = "https://test.local";
 
-pub fn create_client<F>(underlying_client: MockClient<F>) -> DomeneshopClient
+pub fn create_client<F>(http_client: MockClient<F>) -> DomeneshopClient<MockClient<F>>
 where
     F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
 {
-    DomeneshopClient::new(
-        String::from("token"),
-        String::from("secret"),
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        http_client,
         DomeneshopClientConfiguration {
-            user_agent: None,
             base_url: Some(TEST_BASE_URL.to_string()),
-            underlying_client: Some(Box::new(underlying_client)),
+            ..Default::default()
         },
     )
     .unwrap()
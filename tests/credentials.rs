@@ -0,0 +1,222 @@
+use std::{
+    error::Error,
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    errors::DomeneshopError,
+    http_client::mock::MockClient,
+    retry::RetryPolicy,
+};
+use http_types::{Request, Response, StatusCode};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+const TOKEN: &str = "token-9f2c61";
+const SECRET: &str = "secret-b7e4d0";
+// base64 of "token-9f2c61:secret-b7e4d0"
+const ENCODED: &str = "dG9rZW4tOWYyYzYxOnNlY3JldC1iN2U0ZDA";
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+fn create_client<F>(http_client: MockClient<F>) -> DomeneshopClient<MockClient<F>>
+where
+    F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
+{
+    DomeneshopClient::with_http_client(
+        Credentials::new(TOKEN, SECRET),
+        http_client,
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy {
+                initial_backoff: Duration::ZERO,
+                ..RetryPolicy::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn assert_no_secret(output: &str) {
+    assert!(!output.contains(TOKEN), "token leaked: {}", output);
+    assert!(!output.contains(SECRET), "secret leaked: {}", output);
+    assert!(!output.contains(ENCODED), "header leaked: {}", output);
+}
+
+fn assert_error_has_no_secret(err: &DomeneshopError) {
+    let mut output = format!("{} {:?}", err, err);
+    let mut source = err.source();
+    while let Some(err) = source {
+        output.push_str(&format!(" {} {:?}", err, err));
+        source = err.source();
+    }
+    assert_no_secret(&output);
+}
+
+async fn return_unauthorized(req: Request) -> Result<Response, DomeneshopError> {
+    assert_eq!(
+        req.header("Authorization").unwrap(),
+        format!("Basic {}", ENCODED).as_str()
+    );
+    let mut response = Response::new(StatusCode::Unauthorized);
+    response.set_body("{\"code\": \"unauthorized\", \"help\": \"Invalid credentials\"}");
+    Ok(response)
+}
+
+async fn return_server_error(_: Request) -> Result<Response, DomeneshopError> {
+    Ok(Response::new(StatusCode::InternalServerError))
+}
+
+async fn return_transport_error(_: Request) -> Result<Response, DomeneshopError> {
+    Err(DomeneshopError::transport(
+        "Connection reset",
+        std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset by peer"),
+    ))
+}
+
+async fn return_invalid_body(_: Request) -> Result<Response, DomeneshopError> {
+    let mut response = Response::new(StatusCode::Ok);
+    response.set_body("not json");
+    Ok(response)
+}
+
+#[test]
+fn credentials_are_redacted_in_debug_output() {
+    let credentials = Credentials::new(TOKEN, SECRET);
+
+    assert_no_secret(&format!(
+        "{:?} {:#?} {}",
+        credentials, credentials, credentials
+    ));
+}
+
+#[tokio::test]
+async fn errors_do_not_contain_secret() {
+    let errors = vec![
+        create_client(MockClient {
+            req_received: return_unauthorized,
+        })
+        .list_domains()
+        .await
+        .unwrap_err(),
+        create_client(MockClient {
+            req_received: return_server_error,
+        })
+        .list_domains()
+        .await
+        .unwrap_err(),
+        create_client(MockClient {
+            req_received: return_transport_error,
+        })
+        .list_domains()
+        .await
+        .unwrap_err(),
+        create_client(MockClient {
+            req_received: return_invalid_body,
+        })
+        .list_domains()
+        .await
+        .unwrap_err(),
+    ];
+
+    for err in &errors {
+        assert_error_has_no_secret(err);
+    }
+}
+
+#[test]
+fn builder_errors_do_not_contain_secret() {
+    let err = DomeneshopClient::builder()
+        .credentials(TOKEN, SECRET)
+        .user_agent("invalid\n")
+        .build()
+        .err()
+        .unwrap();
+
+    assert_error_has_no_secret(&err);
+}
+
+#[cfg(unix)]
+#[test]
+fn config_file_errors_do_not_contain_secret() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = std::env::temp_dir().join(format!(
+        "domeneshop-client-leak-{}.toml",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        format!("[default]\ntoken = \"{}\"\nsecret = \"{}\n", TOKEN, SECRET),
+    )
+    .unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    let result = DomeneshopClient::from_config_file_with_configuration(
+        &path,
+        "default",
+        DomeneshopClientConfiguration::default(),
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    let err = result.err().unwrap();
+    assert!(err.to_string().contains("line 3"));
+    assert_error_has_no_secret(&err);
+}
+
+#[tokio::test]
+async fn logs_do_not_contain_secret() {
+    let buffer = SharedBuffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_span_events(FmtSpan::FULL)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    _ = create_client(MockClient {
+        req_received: return_unauthorized,
+    })
+    .list_domains()
+    .await;
+    _ = create_client(MockClient {
+        req_received: return_server_error,
+    })
+    .get_domain(1)
+    .await;
+    _ = create_client(MockClient {
+        req_received: return_transport_error,
+    })
+    .list_dns_records(1)
+    .await;
+
+    let output = buffer.contents();
+    assert!(output.contains("list_domains"));
+    assert_no_secret(&output);
+}
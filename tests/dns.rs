@@ -1,7 +1,10 @@
 use domeneshop_client::{
     self,
-    endpoints::dns::{CNAMERecordData, DnsRecordData, DnsType, ExistingDnsRecord},
-    errors::DomeneshopError,
+    endpoints::dns::{
+        CAARecordData, CAATag, CNAMERecordData, DnsRecordData, DnsType, ExistingDnsRecord,
+        MXRecordData, RecordField, Ttl,
+    },
+    errors::{DomeneshopError, DomeneshopErrorKind},
     http_client::mock::MockClient,
 };
 use http_types::{Method, Request, Response, StatusCode};
@@ -15,7 +18,7 @@ async fn get_dns_deserializes_correctly() {
         assert_url_equal(req.url(), "/domains/3/dns/2");
         let mut response = Response::new(StatusCode::Ok);
         response
-            .set_body("{\"id\": 2, \"host\":\"t\", \"ttl\": 1, \"type\": \"A\", \"data\": \"a\"}");
+            .set_body("{\"id\": 2, \"host\":\"t\", \"ttl\": 3600, \"type\": \"A\", \"data\": \"192.168.0.1\"}");
         Ok(response)
     }
 
@@ -121,7 +124,7 @@ async fn list_dns_deserializes_a_record_correctly() {
     async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
         let mut response = Response::new(StatusCode::Ok);
         response.set_body(
-            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 1, \"type\": \"A\", \"data\": \"a\"}]",
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 3600, \"type\": \"A\", \"data\": \"192.168.0.1\"}]",
         );
         Ok(response)
     }
@@ -145,7 +148,7 @@ async fn list_dns_deserializes_aaaa_record_correctly() {
     async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
         let mut response = Response::new(StatusCode::Ok);
         response.set_body(
-            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 1, \"type\": \"AAAA\", \"data\": \"a\"}]",
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 3600, \"type\": \"AAAA\", \"data\": \"::1\"}]",
         );
         Ok(response)
     }
@@ -169,7 +172,7 @@ async fn list_dns_deserializes_cname_record_correctly() {
     async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
         let mut response = Response::new(StatusCode::Ok);
         response.set_body(
-            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 1, \"type\": \"CNAME\", \"data\": \"a\"}]",
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 3600, \"type\": \"CNAME\", \"data\": \"a\"}]",
         );
         Ok(response)
     }
@@ -193,7 +196,7 @@ async fn list_dns_deserializes_mx_record_correctly() {
     async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
         let mut response = Response::new(StatusCode::Ok);
         response.set_body(
-            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 1, \"type\": \"MX\", \"data\": \"a\", \"priority\": \"1\"}]",
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 3600, \"type\": \"MX\", \"data\": \"a\", \"priority\": \"1\"}]",
         );
         Ok(response)
     }
@@ -217,7 +220,7 @@ async fn list_dns_deserializes_srv_record_correctly() {
     async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
         let mut response = Response::new(StatusCode::Ok);
         response.set_body(
-            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 1, \"type\": \"SRV\", \"data\": \"a\", \"priority\": \"1\", \"weight\": \"1\", \"port\": \"1\"}]",
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 3600, \"type\": \"SRV\", \"data\": \"a\", \"priority\": \"1\", \"weight\": \"1\", \"port\": \"1\"}]",
         );
         Ok(response)
     }
@@ -241,7 +244,7 @@ async fn list_dns_deserializes_txt_record_correctly() {
     async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
         let mut response = Response::new(StatusCode::Ok);
         response.set_body(
-            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 1, \"type\": \"TXT\", \"data\": \"a\"}]",
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 3600, \"type\": \"TXT\", \"data\": \"a\"}]",
         );
         Ok(response)
     }
@@ -265,7 +268,7 @@ async fn add_dns_serializes_response_correctly() {
     async fn receive_request(mut req: Request) -> Result<Response, DomeneshopError> {
         assert_url_equal(req.url(), "/domains/3/dns");
         let json = req.body_string().await.unwrap();
-        let expected = "{\"type\":\"CNAME\",\"host\":\"t\",\"ttl\":1,\"data\":\"a\"}";
+        let expected = "{\"type\":\"CNAME\",\"host\":\"t\",\"ttl\":3600,\"data\":\"a\"}";
         assert_eq!(json, expected);
 
         let mut response = Response::new(StatusCode::Created);
@@ -281,7 +284,7 @@ async fn add_dns_serializes_response_correctly() {
 
     let record = DnsRecordData::CNAME(CNAMERecordData {
         host: "t".to_string(),
-        ttl: 1,
+        ttl: Ttl::HOUR,
         data: "a".to_string(),
     });
 
@@ -306,7 +309,7 @@ async fn add_dns_gets_url_from_location_header() {
 
     let record = DnsRecordData::CNAME(CNAMERecordData {
         host: "t".to_string(),
-        ttl: 1,
+        ttl: Ttl::HOUR,
         data: "a".to_string(),
     });
 
@@ -331,7 +334,7 @@ async fn add_dns_invalid_url_in_location_header_results_in_none() {
 
     let record = DnsRecordData::CNAME(CNAMERecordData {
         host: "t".to_string(),
-        ttl: 1,
+        ttl: Ttl::HOUR,
         data: "a".to_string(),
     });
 
@@ -355,7 +358,7 @@ async fn add_dns_missing_location_header_results_in_none_url() {
 
     let record = DnsRecordData::CNAME(CNAMERecordData {
         host: "t".to_string(),
-        ttl: 1,
+        ttl: Ttl::HOUR,
         data: "a".to_string(),
     });
 
@@ -368,7 +371,7 @@ async fn update_dns_serializes_correctly() {
     async fn receive_request(mut req: Request) -> Result<Response, DomeneshopError> {
         assert_url_equal(req.url(), "/domains/3/dns/1");
         let json = req.body_string().await.unwrap();
-        let expected = "{\"type\":\"CNAME\",\"host\":\"t\",\"ttl\":1,\"data\":\"a\"}";
+        let expected = "{\"type\":\"CNAME\",\"host\":\"t\",\"ttl\":3600,\"data\":\"a\"}";
         assert_eq!(json, expected);
         Ok(Response::new(StatusCode::NoContent))
     }
@@ -383,7 +386,7 @@ async fn update_dns_serializes_correctly() {
         id: 1,
         data: DnsRecordData::CNAME(CNAMERecordData {
             host: "t".to_string(),
-            ttl: 1,
+            ttl: Ttl::HOUR,
             data: "a".to_string(),
         }),
     };
@@ -422,4 +425,292 @@ async fn delete_dns_404_returns_err() {
 
     let response = client.delete_dns_record(3, 5).await;
     assert!(response.is_err());
+    assert_eq!(
+        response.unwrap_err().kind(),
+        &DomeneshopErrorKind::UnexpectedStatus(StatusCode::NotFound)
+    );
+}
+
+#[tokio::test]
+async fn add_dns_api_error_is_parsed() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::BadRequest);
+        response.set_body("{\"code\": \"dns:invalid\", \"help\": \"Invalid record\"}");
+        Ok(response)
+    }
+
+    let mock = MockClient {
+        req_received: receive_request,
+    };
+
+    let client = create_client(mock);
+
+    let record = DnsRecordData::CNAME(CNAMERecordData {
+        host: "t".to_string(),
+        ttl: Ttl::HOUR,
+        data: "a".to_string(),
+    });
+
+    let error = client.add_dns_record(3, record).await.unwrap_err();
+    assert_eq!(
+        error.kind(),
+        &DomeneshopErrorKind::Api {
+            status: StatusCode::BadRequest,
+            code: "dns:invalid".to_string(),
+            help: "Invalid record".to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn add_dns_unexpected_status_returns_err() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        Ok(Response::new(StatusCode::Ok))
+    }
+
+    let mock = MockClient {
+        req_received: receive_request,
+    };
+
+    let client = create_client(mock);
+
+    let record = DnsRecordData::CNAME(CNAMERecordData {
+        host: "t".to_string(),
+        ttl: Ttl::HOUR,
+        data: "a".to_string(),
+    });
+
+    let error = client.add_dns_record(3, record).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::Ok));
+}
+
+#[tokio::test]
+async fn list_dns_invalid_body_returns_deserialization_error() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("{");
+        Ok(response)
+    }
+
+    let mock = MockClient {
+        req_received: receive_request,
+    };
+
+    let client = create_client(mock);
+
+    let error = client.list_dns_records(3).await.unwrap_err();
+    assert_eq!(error.kind(), &DomeneshopErrorKind::Deserialization);
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[tokio::test]
+async fn list_dns_deserializes_all_record_types() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body(
+            r#"[
+                {"id": 1, "host": "@", "ttl": 3600, "type": "ANAME", "data": "example.net"},
+                {"id": 2, "host": "@", "ttl": 3600, "type": "CAA", "data": "letsencrypt.org", "flags": 0, "tag": 0},
+                {"id": 3, "host": "sub", "ttl": 3600, "type": "NS", "data": "ns1.example.net"},
+                {"id": 4, "host": "sub", "ttl": 3600, "type": "DS", "data": "2bb183af5f2250", "tag": 12345, "alg": 13, "digest": 2},
+                {"id": 5, "host": "_443._tcp.www", "ttl": 3600, "type": "TLSA", "data": "d2abde240d7cd3", "usage": 3, "selector": 1, "dtype": "1"},
+                {"id": 6, "host": "@", "ttl": 3600, "type": "CAA", "data": "mailto:security@example.com", "flags": "128", "tag": "iodef"}
+            ]"#,
+        );
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let records = client.list_dns_records(3).await.unwrap();
+
+    let types: Vec<_> = records
+        .iter()
+        .map(|record| record.data.dns_type())
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            DnsType::ANAME,
+            DnsType::CAA,
+            DnsType::NS,
+            DnsType::DS,
+            DnsType::TLSA,
+            DnsType::CAA
+        ]
+    );
+    match &records[3].data {
+        DnsRecordData::DS(record) => {
+            assert_eq!((record.tag, record.alg, record.digest), (12345, 13, 2))
+        }
+        _ => panic!("Wrong record type"),
+    }
+    match &records[4].data {
+        DnsRecordData::TLSA(record) => {
+            assert_eq!((record.usage, record.selector, record.dtype), (3, 1, 1))
+        }
+        _ => panic!("Wrong record type"),
+    }
+    match &records[5].data {
+        DnsRecordData::CAA(record) => assert_eq!((record.flags, record.tag), (128, CAATag::Iodef)),
+        _ => panic!("Wrong record type"),
+    }
+}
+
+#[tokio::test]
+async fn add_caa_record_serializes_fields() {
+    async fn receive_request(mut req: Request) -> Result<Response, DomeneshopError> {
+        let body: serde_json::Value = req.body_json().await.unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "type": "CAA",
+                "host": "@",
+                "ttl": 3600,
+                "data": "letsencrypt.org",
+                "flags": 0,
+                "tag": 1
+            })
+        );
+        let mut response = Response::new(StatusCode::Created);
+        response.set_body("{\"id\": 7}");
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let record = DnsRecordData::CAA(CAARecordData {
+        host: "@".to_string(),
+        ttl: Ttl::HOUR,
+        data: "letsencrypt.org".to_string(),
+        flags: 0,
+        tag: CAATag::IssueWild,
+    });
+
+    assert_eq!(client.add_dns_record(3, record).await.unwrap().id, 7);
+}
+
+#[tokio::test]
+async fn list_dns_accepts_numbers_as_strings() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body(
+            "[{\"id\": 1, \"host\":\"_sip._tcp\", \"ttl\": \"300\", \"type\": \"SRV\", \"data\": \"sip.example.com\", \"priority\": 10, \"weight\": \"5\", \"port\": \"5060\"}]",
+        );
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let response = client.list_dns_records(3).await.unwrap();
+    match &response[0].data {
+        DnsRecordData::SRV(srv) => {
+            assert_eq!(srv.ttl.seconds(), 300);
+            assert_eq!((srv.priority, srv.weight, srv.port), (10, 5, 5060));
+        }
+        _ => panic!("Wrong record type"),
+    }
+}
+
+#[tokio::test]
+async fn list_dns_invalid_ttl_is_deserialized_and_reported_by_validate() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body(
+            "[{\"id\": 1, \"host\":\"t\", \"ttl\": 90, \"type\": \"A\", \"data\": \"192.168.0.1\"}]",
+        );
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let records = client.list_dns_records(3).await.unwrap();
+    assert_eq!(records[0].data.ttl().seconds(), 90);
+
+    let fields: Vec<_> = records[0]
+        .data
+        .validate()
+        .into_iter()
+        .map(|violation| violation.field)
+        .collect();
+    assert_eq!(fields, vec![RecordField::Ttl]);
+}
+
+#[tokio::test]
+async fn add_mx_record_serializes_priority_as_string() {
+    async fn receive_request(mut req: Request) -> Result<Response, DomeneshopError> {
+        let body: serde_json::Value = req.body_json().await.unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "type": "MX",
+                "host": "@",
+                "ttl": 3600,
+                "data": "mail.example.com",
+                "priority": "10"
+            })
+        );
+        let mut response = Response::new(StatusCode::Created);
+        response.set_body("{\"id\": 8}");
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let record = DnsRecordData::MX(MXRecordData {
+        host: "@".to_string(),
+        ttl: Ttl::HOUR,
+        data: "mail.example.com".to_string(),
+        priority: 10,
+    });
+
+    assert_eq!(client.add_dns_record(3, record).await.unwrap().id, 8);
+}
+
+#[tokio::test]
+async fn invalid_record_is_not_sent() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        panic!("Invalid record should not be sent")
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let record = DnsRecordData::CNAME(CNAMERecordData {
+        host: "-t".to_string(),
+        ttl: Ttl::HOUR,
+        data: "not a host".to_string(),
+    });
+
+    let error = client
+        .update_dns_record(
+            3,
+            ExistingDnsRecord {
+                id: 1,
+                data: record,
+            },
+        )
+        .await
+        .unwrap_err();
+    match error.kind() {
+        DomeneshopErrorKind::InvalidRecord(violations) => {
+            let fields: Vec<_> = violations.iter().map(|violation| violation.field).collect();
+            assert_eq!(fields, vec![RecordField::Host, RecordField::Data]);
+        }
+        kind => panic!("Wrong error kind {:?}", kind),
+    }
+    assert!(error
+        .to_string()
+        .starts_with("Invalid CNAME-record: host: "));
 }
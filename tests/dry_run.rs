@@ -0,0 +1,139 @@
+use std::net::IpAddr;
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    dry_run::DryRun,
+    endpoints::{
+        dns::{DnsRecordData, ExistingDnsRecord, TXTRecordData, Ttl},
+        forwards::HttpForward,
+    },
+    http_client::fake::{self, FakeDomeneshop},
+};
+use http_types::Method;
+use url::Url;
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client(fake: &FakeDomeneshop, dry_run: &DryRun) -> DomeneshopClient<FakeDomeneshop> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        fake.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            dry_run: Some(dry_run.clone()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn create_fake() -> FakeDomeneshop {
+    let fake = FakeDomeneshop::new();
+    fake.add_domain(fake::domain(1, "example.com"));
+    fake
+}
+
+fn txt_record(data: &str) -> DnsRecordData {
+    DnsRecordData::TXT(TXTRecordData {
+        host: "@".to_string(),
+        ttl: Ttl::HOUR,
+        data: data.to_string(),
+    })
+}
+
+#[tokio::test]
+async fn reads_are_sent() {
+    let fake = create_fake();
+    let dry_run = DryRun::new();
+    fake.add_dns_record(1, txt_record("existing"));
+    let client = create_client(&fake, &dry_run);
+
+    let domains = client.list_domains().await.unwrap();
+    let records = client.list_dns_records(1).await.unwrap();
+
+    assert_eq!(domains.len(), 1);
+    assert_eq!(records.len(), 1);
+    assert!(dry_run.operations().is_empty());
+}
+
+#[tokio::test]
+async fn dns_mutations_are_recorded_and_not_sent() {
+    let fake = create_fake();
+    let dry_run = DryRun::new();
+    let existing = fake.add_dns_record(1, txt_record("existing"));
+    let client = create_client(&fake, &dry_run);
+
+    let added = client.add_dns_record(1, txt_record("new")).await.unwrap();
+    client
+        .update_dns_record(
+            1,
+            ExistingDnsRecord {
+                id: existing,
+                data: txt_record("updated"),
+            },
+        )
+        .await
+        .unwrap();
+    client.delete_dns_record(1, existing).await.unwrap();
+
+    let records = client.list_dns_records(1).await.unwrap();
+    let operations = dry_run.operations();
+    assert_eq!(added.id, 1);
+    assert_eq!(
+        added.url.unwrap().as_str(),
+        "https://test.local/v0/domains/1/dns/1"
+    );
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].data, txt_record("existing"));
+    assert_eq!(
+        operations
+            .iter()
+            .map(|operation| operation.method)
+            .collect::<Vec<_>>(),
+        vec![Method::Post, Method::Put, Method::Delete]
+    );
+    assert_eq!(
+        operations[1].url.path(),
+        format!("/v0/domains/1/dns/{}", existing)
+    );
+    assert!(operations[1].body.as_ref().unwrap().contains("updated"));
+}
+
+#[tokio::test]
+async fn forward_mutations_are_recorded_and_not_sent() {
+    let fake = create_fake();
+    let dry_run = DryRun::new();
+    let client = create_client(&fake, &dry_run);
+    let forward = HttpForward {
+        host: "www".to_string(),
+        frame: false,
+        url: Url::parse("https://example.org").unwrap(),
+    };
+
+    client.add_forward(1, forward.clone()).await.unwrap();
+    client.update_forward(1, forward).await.unwrap();
+    client.delete_forward(1, "www".to_string()).await.unwrap();
+
+    assert!(client.list_forwards(1).await.unwrap().is_empty());
+    assert_eq!(dry_run.operations().len(), 3);
+}
+
+#[tokio::test]
+async fn dyndns_is_recorded_and_not_sent() {
+    let fake = create_fake();
+    let dry_run = DryRun::new();
+    let client = create_client(&fake, &dry_run);
+
+    client
+        .update_dyndns("home.example.com", Some(IpAddr::from([10, 0, 0, 1])))
+        .await
+        .unwrap();
+
+    assert!(client.list_dns_records(1).await.unwrap().is_empty());
+    assert_eq!(
+        dry_run.operations()[0].url.query(),
+        Some("hostname=home.example.com&myip=10.0.0.1")
+    );
+}
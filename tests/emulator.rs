@@ -0,0 +1,206 @@
+use std::{
+    io::{BufRead, BufReader},
+    net::{IpAddr, Ipv4Addr},
+    process::{Child, Command, Stdio},
+};
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    endpoints::{
+        dns::{ARecordData, DnsRecordData, ExistingDnsRecord, Ttl},
+        forwards::HttpForward,
+    },
+    errors::DomeneshopErrorKind,
+    http_client::fake::{self, FakeState},
+    retry::RetryPolicy,
+};
+use http_types::StatusCode;
+use url::Url;
+
+/// A running emulator binary, killed on drop
+struct EmulatorProcess {
+    child: Child,
+    base_url: String,
+    fixture: std::path::PathBuf,
+}
+
+impl EmulatorProcess {
+    fn start(name: &str, state: &FakeState) -> Self {
+        let fixture = std::env::temp_dir().join(format!(
+            "domeneshop-client-emulator-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        std::fs::write(&fixture, serde_json::to_string(state).unwrap()).unwrap();
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_domeneshop-emulator"))
+            .args([
+                "--bind",
+                "127.0.0.1:0",
+                "--token",
+                "token",
+                "--secret",
+                "secret",
+            ])
+            .arg("--fixture")
+            .arg(&fixture)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(child.stdout.take().unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        let base_url = line
+            .trim()
+            .strip_prefix("Listening on ")
+            .unwrap_or_else(|| panic!("Unexpected output: {}", line))
+            .to_string();
+
+        EmulatorProcess {
+            child,
+            base_url,
+            fixture,
+        }
+    }
+
+    fn client(&self, secret: &str) -> DomeneshopClient {
+        DomeneshopClient::new(
+            String::from("token"),
+            String::from(secret),
+            DomeneshopClientConfiguration {
+                base_url: Some(self.base_url.clone()),
+                retry_policy: RetryPolicy::disabled(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+}
+
+impl Drop for EmulatorProcess {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+        _ = std::fs::remove_file(&self.fixture);
+    }
+}
+
+fn seeded_state() -> FakeState {
+    let mut state = FakeState {
+        domains: vec![fake::domain(1, "example.com")],
+        ..Default::default()
+    };
+    state.dns.insert(
+        1,
+        vec![ExistingDnsRecord {
+            id: 10,
+            data: a_record("www", "192.168.0.1"),
+        }],
+    );
+    state
+}
+
+fn a_record(host: &str, data: &str) -> DnsRecordData {
+    DnsRecordData::A(ARecordData {
+        host: host.to_string(),
+        ttl: Ttl::HOUR,
+        data: data.parse().unwrap(),
+    })
+}
+
+#[tokio::test]
+async fn serves_seeded_state() {
+    let emulator = EmulatorProcess::start("seeded", &seeded_state());
+    let client = emulator.client("secret");
+
+    let domains = client.list_domains().await.unwrap();
+    let records = client.list_dns_records(1).await.unwrap();
+
+    assert_eq!(domains.len(), 1);
+    assert_eq!(domains[0].domain, "example.com");
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, 10);
+}
+
+#[tokio::test]
+async fn keeps_changes_between_requests() {
+    let emulator = EmulatorProcess::start("changes", &seeded_state());
+    let client = emulator.client("secret");
+
+    let added = client
+        .add_dns_record(1, a_record("api", "192.168.0.2"))
+        .await
+        .unwrap();
+    client.delete_dns_record(1, 10).await.unwrap();
+    client
+        .add_forward(
+            1,
+            HttpForward {
+                host: "shop".to_string(),
+                frame: false,
+                url: Url::parse("https://example.net").unwrap(),
+            },
+        )
+        .await
+        .unwrap();
+    client
+        .update_dyndns(
+            "home.example.com",
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        )
+        .await
+        .unwrap();
+
+    let records = client.list_dns_records(1).await.unwrap();
+    let forwards = client.list_forwards(1).await.unwrap();
+
+    assert_eq!(
+        added.url.unwrap().as_str(),
+        format!("{}/v0/domains/1/dns/{}", emulator.base_url, added.id)
+    );
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id, added.id);
+    assert_eq!(records[1].data.host(), "home");
+    assert_eq!(forwards.len(), 1);
+}
+
+#[tokio::test]
+async fn responds_with_api_errors() {
+    let emulator = EmulatorProcess::start("errors", &seeded_state());
+    let client = emulator.client("secret");
+
+    let err = client
+        .add_dns_record(1, a_record("www", "192.168.0.1"))
+        .await
+        .unwrap_err();
+
+    match err.kind() {
+        DomeneshopErrorKind::Api { status, code, .. } => {
+            assert_eq!(*status, StatusCode::Conflict);
+            assert_eq!(code, "record:collision");
+        }
+        kind => panic!("Wrong error kind {:?}", kind),
+    }
+}
+
+#[tokio::test]
+async fn checks_basic_auth() {
+    let emulator = EmulatorProcess::start("auth", &seeded_state());
+    let client = emulator.client("wrong");
+
+    let err = client.list_domains().await.unwrap_err();
+    let unauthenticated = reqwest::get(format!("{}/v0/domains", emulator.base_url))
+        .await
+        .unwrap();
+
+    match err.kind() {
+        DomeneshopErrorKind::Api { status, .. } => assert_eq!(*status, StatusCode::Unauthorized),
+        kind => panic!("Wrong error kind {:?}", kind),
+    }
+    assert_eq!(unauthenticated.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert!(unauthenticated
+        .headers()
+        .contains_key(reqwest::header::WWW_AUTHENTICATE));
+}
@@ -0,0 +1,383 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use chrono::NaiveDate;
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::{
+        dns::{
+            ARecordData, CNAMERecordData, DnsRecordData, DnsType, ExistingDnsRecord, NSRecordData,
+            Ttl,
+        },
+        forwards::HttpForward,
+        invoices::{Invoice, InvoiceCurrency, InvoiceStatus, InvoiceType},
+    },
+    errors::DomeneshopErrorKind,
+    http_client::fake::{self, FakeDomeneshop},
+    retry::RetryPolicy,
+};
+use http_types::{Method, StatusCode};
+use serde_json::json;
+use url::Url;
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client(fake: &FakeDomeneshop) -> DomeneshopClient<FakeDomeneshop> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        fake.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy::disabled(),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn create_fake() -> FakeDomeneshop {
+    let fake = FakeDomeneshop::new().with_credentials("token", "secret");
+    fake.add_domain(fake::domain(1, "example.com"));
+    fake
+}
+
+fn a_record(host: &str, ttl: u32, data: &str) -> DnsRecordData {
+    DnsRecordData::A(ARecordData {
+        host: host.to_string(),
+        ttl: Ttl::new(ttl).unwrap(),
+        data: data.parse().unwrap(),
+    })
+}
+
+async fn post_raw_record(
+    client: &DomeneshopClient<FakeDomeneshop>,
+    record: serde_json::Value,
+) -> Result<serde_json::Value, domeneshop_client::errors::DomeneshopError> {
+    client
+        .request(Method::Post, "/domains/1/dns", &[], Some(&record))
+        .await
+}
+
+fn cname_record(host: &str) -> DnsRecordData {
+    DnsRecordData::CNAME(CNAMERecordData {
+        host: host.to_string(),
+        ttl: Ttl::HOUR,
+        data: "example.net".to_string(),
+    })
+}
+
+fn forward(host: &str) -> HttpForward {
+    HttpForward {
+        host: host.to_string(),
+        frame: false,
+        url: Url::parse("https://example.net").unwrap(),
+    }
+}
+
+fn invoice(id: i32, status: InvoiceStatus) -> Invoice {
+    Invoice {
+        id,
+        r#type: InvoiceType::Invoice,
+        amount: 120,
+        currency: InvoiceCurrency::NOK,
+        due_date: None,
+        issued_date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        paid_date: None,
+        status,
+        url: "https://www.domeneshop.no/invoice".to_string(),
+    }
+}
+
+fn assert_api_error(
+    result: Result<impl std::fmt::Debug, domeneshop_client::errors::DomeneshopError>,
+    status: StatusCode,
+    code: &str,
+) {
+    let err = result.unwrap_err();
+    match err.kind() {
+        DomeneshopErrorKind::Api {
+            status: actual_status,
+            code: actual_code,
+            ..
+        } => {
+            assert_eq!(*actual_status, status);
+            assert_eq!(actual_code, code);
+        }
+        kind => panic!("Wrong error kind {:?}", kind),
+    }
+}
+
+#[tokio::test]
+async fn added_dns_record_is_listed() {
+    let fake = create_fake();
+    let client = create_client(&fake);
+
+    let added = client
+        .add_dns_record(1, a_record("www", 3600, "192.168.0.1"))
+        .await
+        .unwrap();
+    let records = client.list_dns_records(1).await.unwrap();
+
+    assert_eq!(
+        added.url.unwrap().as_str(),
+        format!("{}/v0/domains/1/dns/{}", TEST_BASE_URL, added.id)
+    );
+    assert_eq!(
+        records,
+        vec![ExistingDnsRecord {
+            id: added.id,
+            data: a_record("www", 3600, "192.168.0.1"),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn dns_record_can_be_updated_and_deleted() {
+    let fake = create_fake();
+    let id = fake.add_dns_record(1, a_record("www", 3600, "192.168.0.1"));
+    let client = create_client(&fake);
+
+    client
+        .update_dns_record(
+            1,
+            ExistingDnsRecord {
+                id,
+                data: a_record("www", 60, "192.168.0.2"),
+            },
+        )
+        .await
+        .unwrap();
+    let updated = client.get_dns_record(1, id).await.unwrap();
+    client.delete_dns_record(1, id).await.unwrap();
+
+    assert_eq!(updated.data, a_record("www", 60, "192.168.0.2"));
+    assert!(fake.dns_records(1).is_empty());
+    assert_api_error(
+        client.get_dns_record(1, id).await,
+        StatusCode::NotFound,
+        "record:not_found",
+    );
+}
+
+#[tokio::test]
+async fn dns_records_can_be_filtered() {
+    let fake = create_fake();
+    fake.add_dns_record(1, a_record("www", 3600, "192.168.0.1"));
+    fake.add_dns_record(1, a_record("@", 3600, "192.168.0.1"));
+    fake.add_dns_record(1, cname_record("blog"));
+    let client = create_client(&fake);
+
+    let www = client
+        .list_dns_records_with_filter(1, Some("www".to_string()), None)
+        .await
+        .unwrap();
+    let cnames = client
+        .list_dns_records_with_filter(1, None, Some(DnsType::CNAME))
+        .await
+        .unwrap();
+
+    assert_eq!(www.len(), 1);
+    assert_eq!(cnames.len(), 1);
+}
+
+#[tokio::test]
+async fn ttl_must_be_multiple_of_60() {
+    let client = create_client(&create_fake());
+
+    assert_api_error(
+        post_raw_record(
+            &client,
+            json!({"host": "www", "ttl": 61, "type": "A", "data": "192.168.0.1"}),
+        )
+        .await,
+        StatusCode::BadRequest,
+        "record:invalid_ttl",
+    );
+}
+
+#[tokio::test]
+async fn invalid_record_data_is_rejected() {
+    let client = create_client(&create_fake());
+
+    assert_api_error(
+        post_raw_record(
+            &client,
+            json!({"host": "www", "ttl": 60, "type": "A", "data": "not an ip"}),
+        )
+        .await,
+        StatusCode::BadRequest,
+        "record:invalid_data",
+    );
+}
+
+#[tokio::test]
+async fn ns_record_at_apex_is_rejected() {
+    let client = create_client(&create_fake());
+    let ns_record = |host: &str| {
+        DnsRecordData::NS(NSRecordData {
+            host: host.to_string(),
+            ttl: Ttl::HOUR,
+            data: "ns1.example.net".to_string(),
+        })
+    };
+
+    assert_api_error(
+        client.add_dns_record(1, ns_record("@")).await,
+        StatusCode::BadRequest,
+        "record:invalid_data",
+    );
+    assert!(client.add_dns_record(1, ns_record("sub")).await.is_ok());
+}
+
+#[tokio::test]
+async fn colliding_records_are_rejected() {
+    let fake = create_fake();
+    fake.add_dns_record(1, a_record("www", 3600, "192.168.0.1"));
+    fake.add_dns_record(1, cname_record("blog"));
+    let client = create_client(&fake);
+
+    assert_api_error(
+        client
+            .add_dns_record(1, a_record("www", 3600, "192.168.0.1"))
+            .await,
+        StatusCode::Conflict,
+        "record:collision",
+    );
+    assert_api_error(
+        client.add_dns_record(1, cname_record("www")).await,
+        StatusCode::Conflict,
+        "record:collision",
+    );
+    assert_api_error(
+        client
+            .add_dns_record(1, a_record("blog", 3600, "192.168.0.1"))
+            .await,
+        StatusCode::Conflict,
+        "record:collision",
+    );
+}
+
+#[tokio::test]
+async fn forwards_collide_with_records_and_forwards() {
+    let fake = create_fake();
+    fake.add_dns_record(1, a_record("www", 3600, "192.168.0.1"));
+    let client = create_client(&fake);
+
+    client.add_forward(1, forward("shop")).await.unwrap();
+
+    assert_api_error(
+        client.add_forward(1, forward("shop")).await,
+        StatusCode::Conflict,
+        "forward:collision",
+    );
+    assert_api_error(
+        client.add_forward(1, forward("www")).await,
+        StatusCode::Conflict,
+        "forward:collision",
+    );
+    assert_api_error(
+        client.add_dns_record(1, cname_record("shop")).await,
+        StatusCode::Conflict,
+        "record:collision",
+    );
+}
+
+#[tokio::test]
+async fn forward_lifecycle() {
+    let fake = create_fake();
+    let client = create_client(&fake);
+
+    client.add_forward(1, forward("shop")).await.unwrap();
+    let mut updated = forward("shop");
+    updated.frame = true;
+    client.update_forward(1, updated.clone()).await.unwrap();
+    let fetched = client.get_forward(1, "shop".to_string()).await.unwrap();
+    client.delete_forward(1, "shop".to_string()).await.unwrap();
+    let deleted = client.get_forward(1, "shop".to_string()).await.unwrap();
+
+    assert_eq!(fetched, Some(updated));
+    assert_eq!(deleted, None);
+    assert!(fake.forwards(1).is_empty());
+}
+
+#[tokio::test]
+async fn unknown_domain_responds_not_found() {
+    let client = create_client(&create_fake());
+
+    assert_api_error(
+        client.get_domain(2).await,
+        StatusCode::NotFound,
+        "domain:not_found",
+    );
+    assert_api_error(
+        client.list_dns_records(2).await,
+        StatusCode::NotFound,
+        "domain:not_found",
+    );
+}
+
+#[tokio::test]
+async fn wrong_credentials_respond_unauthorized() {
+    let fake = create_fake();
+    let client = DomeneshopClient::with_http_client(
+        Credentials::new("token", "wrong"),
+        fake,
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_api_error(
+        client.list_domains().await,
+        StatusCode::Unauthorized,
+        "access:unauthorized",
+    );
+}
+
+#[tokio::test]
+async fn domains_and_invoices_are_listed() {
+    let fake = create_fake();
+    fake.add_domain(fake::domain(2, "example.no"));
+    fake.add_invoice(invoice(1, InvoiceStatus::Paid));
+    fake.add_invoice(invoice(2, InvoiceStatus::Unpaid));
+    let client = create_client(&fake);
+
+    let domains = client.list_domains_with_filter(".no").await.unwrap();
+    let unpaid = client
+        .list_invoices_with_status(InvoiceStatus::Unpaid)
+        .await
+        .unwrap();
+    let missing = client.get_invoice(3).await.unwrap();
+
+    assert_eq!(domains.len(), 1);
+    assert_eq!(domains[0].id, 2);
+    assert_eq!(unpaid.len(), 1);
+    assert_eq!(unpaid[0].id, 2);
+    assert_eq!(missing, None);
+}
+
+#[tokio::test]
+async fn dyndns_creates_and_updates_record() {
+    let fake = create_fake();
+    let client = create_client(&fake);
+
+    client
+        .update_dyndns("home.example.com", None)
+        .await
+        .unwrap();
+    client
+        .update_dyndns(
+            "home.example.com",
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        )
+        .await
+        .unwrap();
+
+    let records = fake.dns_records(1);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].data.host(), "home");
+    assert_eq!(records[0].data.data(), "10.0.0.1");
+}
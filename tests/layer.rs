@@ -0,0 +1,239 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    errors::DomeneshopError,
+    http_client::mock::MockClient,
+    layer::{Layer, Next},
+    retry::RetryPolicy,
+};
+use http_types::{Request, Response, StatusCode};
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client<F>(
+    http_client: MockClient<F>,
+    layers: Vec<Box<dyn Layer>>,
+) -> DomeneshopClient<MockClient<F>>
+where
+    F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
+{
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        http_client,
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy {
+                initial_backoff: Duration::ZERO,
+                ..RetryPolicy::default()
+            },
+            layers,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+async fn return_empty_list(_: Request) -> Result<Response, DomeneshopError> {
+    let mut response = Response::new(StatusCode::Ok);
+    response.set_body("[]");
+    Ok(response)
+}
+
+struct HeaderLayer(&'static str);
+
+#[async_trait]
+impl Layer for HeaderLayer {
+    async fn handle(
+        &self,
+        mut request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DomeneshopError> {
+        request.append_header("X-Layers", self.0);
+        next.run(request).await
+    }
+}
+
+struct RecordingLayer {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Layer for RecordingLayer {
+    async fn handle(&self, request: Request, next: Next<'_>) -> Result<Response, DomeneshopError> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} request", self.name));
+        let response = next.run(request).await;
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} response", self.name));
+        response
+    }
+}
+
+#[tokio::test]
+async fn layer_can_modify_request() {
+    async fn receive_request(req: Request) -> Result<Response, DomeneshopError> {
+        let values: Vec<_> = req.header("X-Layers").unwrap().iter().collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0], "first");
+        assert_eq!(values[1], "second");
+        return_empty_list(req).await
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        vec![
+            Box::new(HeaderLayer("first")),
+            Box::new(HeaderLayer("second")),
+        ],
+    );
+
+    client.list_domains().await.unwrap();
+}
+
+#[tokio::test]
+async fn layers_are_invoked_in_order() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let client = create_client(
+        MockClient {
+            req_received: return_empty_list,
+        },
+        vec![
+            Box::new(RecordingLayer {
+                name: "outer",
+                log: log.clone(),
+            }),
+            Box::new(RecordingLayer {
+                name: "inner",
+                log: log.clone(),
+            }),
+        ],
+    );
+
+    client.list_domains().await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "outer request",
+            "inner request",
+            "inner response",
+            "outer response"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn layer_can_modify_response() {
+    struct NotFoundLayer;
+
+    #[async_trait]
+    impl Layer for NotFoundLayer {
+        async fn handle(
+            &self,
+            request: Request,
+            next: Next<'_>,
+        ) -> Result<Response, DomeneshopError> {
+            let mut response = next.run(request).await?;
+            response.set_status(StatusCode::NotFound);
+            Ok(response)
+        }
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: return_empty_list,
+        },
+        vec![Box::new(NotFoundLayer)],
+    );
+
+    let response = client.get_forward(3, "www".to_string()).await.unwrap();
+
+    assert!(response.is_none());
+}
+
+#[tokio::test]
+async fn layer_can_inject_faults_that_are_retried() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    struct FailFirstLayer;
+
+    #[async_trait]
+    impl Layer for FailFirstLayer {
+        async fn handle(
+            &self,
+            request: Request,
+            next: Next<'_>,
+        ) -> Result<Response, DomeneshopError> {
+            if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Ok(Response::new(StatusCode::ServiceUnavailable));
+            }
+            next.run(request).await
+        }
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: return_empty_list,
+        },
+        vec![Box::new(FailFirstLayer)],
+    );
+
+    client.list_domains().await.unwrap();
+
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn layer_can_call_next_twice() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn fail_first(req: Request) -> Result<Response, DomeneshopError> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Ok(Response::new(StatusCode::ServiceUnavailable));
+        }
+        return_empty_list(req).await
+    }
+
+    struct RetryOnceLayer;
+
+    #[async_trait]
+    impl Layer for RetryOnceLayer {
+        async fn handle(
+            &self,
+            request: Request,
+            next: Next<'_>,
+        ) -> Result<Response, DomeneshopError> {
+            let response = next.run(request.clone()).await?;
+            if response.status() != StatusCode::ServiceUnavailable {
+                return Ok(response);
+            }
+            next.run(request).await
+        }
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: fail_first,
+        },
+        vec![Box::new(RetryOnceLayer)],
+    );
+
+    client.list_domains().await.unwrap();
+
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
@@ -0,0 +1,169 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::dns::{DnsRecordData, TXTRecordData, Ttl},
+    http_client::mock::ClosureMockClient,
+    retry::RetryPolicy,
+};
+use http_types::{Method, Response, StatusCode};
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client(mock: &ClosureMockClient) -> DomeneshopClient<ClosureMockClient> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        mock.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy::disabled(),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn expectations_respond_with_canned_responses() {
+    let mock = ClosureMockClient::new();
+    mock.expect(Method::Get, "/domains?domain=.no", StatusCode::Ok, "[]")
+        .expect(
+            Method::Delete,
+            "/domains/1/dns/2",
+            StatusCode::NoContent,
+            "",
+        );
+    let client = create_client(&mock);
+
+    let domains = client.list_domains_with_filter(".no").await.unwrap();
+    client.delete_dns_record(1, 2).await.unwrap();
+
+    assert!(domains.is_empty());
+    assert!(mock.unmet_expectations().is_empty());
+}
+
+#[tokio::test]
+async fn calls_are_recorded_in_order() {
+    let mock = ClosureMockClient::new();
+    mock.expect(
+        Method::Delete,
+        "/domains/1/dns/2",
+        StatusCode::NoContent,
+        "",
+    )
+    .expect(
+        Method::Post,
+        "/domains/1/dns",
+        StatusCode::Created,
+        "{\"id\": 3}",
+    );
+    let client = create_client(&mock);
+
+    client.delete_dns_record(1, 2).await.unwrap();
+    client
+        .add_dns_record(
+            1,
+            DnsRecordData::TXT(TXTRecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "hello".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].method, "DELETE");
+    assert_eq!(calls[0].url, "https://test.local/v0/domains/1/dns/2");
+    assert_eq!(calls[1].method, "POST");
+    assert!(calls[1].body.contains("hello"));
+    assert!(calls[1].headers.contains_key("authorization"));
+}
+
+#[tokio::test]
+async fn handler_can_capture_state() {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let captured = counter.clone();
+    let mock = ClosureMockClient::with_handler(move |_| {
+        let counter = captured.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let mut response = Response::new(StatusCode::Ok);
+            response.set_body("[]");
+            Ok(response)
+        }
+    });
+    mock.expect(Method::Get, "/invoices", StatusCode::Ok, "[]");
+    let client = create_client(&mock);
+
+    client.list_invoices().await.unwrap();
+    client.list_domains().await.unwrap();
+    client.list_domains().await.unwrap();
+
+    assert_eq!(counter.load(Ordering::SeqCst), 2);
+    assert_eq!(mock.calls().len(), 3);
+}
+
+#[tokio::test]
+async fn expectations_can_be_repeated() {
+    let mock = ClosureMockClient::new();
+    mock.expect_times(Method::Get, "/domains", StatusCode::Ok, "[]", 2);
+    let client = create_client(&mock);
+
+    client.list_domains().await.unwrap();
+    client.list_domains().await.unwrap();
+
+    mock.assert_expectations();
+}
+
+#[tokio::test]
+#[should_panic(expected = "Unexpected request GET")]
+async fn unexpected_request_panics_without_handler() {
+    let mock = ClosureMockClient::new();
+    let client = create_client(&mock);
+
+    _ = client.list_domains().await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "expectation(s) of the mock were not met")]
+async fn unmet_expectations_panic_on_drop() {
+    let mock = ClosureMockClient::new();
+    mock.expect(Method::Get, "/domains", StatusCode::Ok, "[]")
+        .expect(Method::Get, "/invoices", StatusCode::Ok, "[]");
+    let client = create_client(&mock);
+
+    client.list_domains().await.unwrap();
+    drop(client);
+    drop(mock);
+}
+
+#[tokio::test]
+async fn expectations_match_base_url_with_path() {
+    let mock = ClosureMockClient::new();
+    mock.expect(Method::Get, "/domains/1/dns", StatusCode::Ok, "[]");
+    let client = DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        mock.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some("https://proxy.local/domeneshop".to_string()),
+            retry_policy: RetryPolicy::disabled(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let records = client.list_dns_records(1).await.unwrap();
+
+    assert!(records.is_empty());
+    assert_eq!(
+        mock.calls()[0].url,
+        "https://proxy.local/domeneshop/v0/domains/1/dns"
+    );
+}
@@ -0,0 +1,115 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    errors::DomeneshopError,
+    http_client::mock::MockClient,
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+};
+use http_types::{Request, Response, StatusCode};
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client<F>(
+    http_client: MockClient<F>,
+    rate_limiter: RateLimiter,
+) -> DomeneshopClient<MockClient<F>>
+where
+    F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
+{
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        http_client,
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy: RetryPolicy {
+                initial_backoff: Duration::ZERO,
+                ..RetryPolicy::default()
+            },
+            rate_limiter: Some(rate_limiter),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+async fn return_empty_list(_: Request) -> Result<Response, DomeneshopError> {
+    let mut response = Response::new(StatusCode::Ok);
+    response.set_body("[]");
+    Ok(response)
+}
+
+#[tokio::test]
+async fn requests_exceeding_burst_are_delayed() {
+    let client = create_client(
+        MockClient {
+            req_received: return_empty_list,
+        },
+        RateLimiter::new(10.0, 1),
+    );
+
+    let start = Instant::now();
+    client.list_domains().await.unwrap();
+    client.list_domains().await.unwrap();
+    client.list_domains().await.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn concurrent_callers_share_budget() {
+    let client = create_client(
+        MockClient {
+            req_received: return_empty_list,
+        },
+        RateLimiter::new(10.0, 2),
+    );
+
+    let start = Instant::now();
+    let (first, second, third, fourth) = tokio::join!(
+        client.list_domains(),
+        client.list_domains(),
+        client.list_domains(),
+        client.list_domains()
+    );
+    first.unwrap();
+    second.unwrap();
+    third.unwrap();
+    fourth.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn throttled_request_respects_retry_after() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            let mut response = Response::new(StatusCode::TooManyRequests);
+            response.insert_header("Retry-After", "1");
+            return Ok(response);
+        }
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        RateLimiter::new(100.0, 10),
+    );
+
+    let start = Instant::now();
+    client.list_domains().await.unwrap();
+
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
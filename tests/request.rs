@@ -0,0 +1,135 @@
+use domeneshop_client::{
+    endpoints::domains::Domain,
+    errors::{DomeneshopError, DomeneshopErrorKind},
+    http_client::mock::MockClient,
+};
+use http_types::{Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::common::{assert_url_equal, create_client};
+mod common;
+
+#[derive(Serialize)]
+struct Renewal {
+    years: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct RenewalResponse {
+    expiry_date: String,
+}
+
+#[tokio::test]
+async fn request_formats_url_and_deserializes_response() {
+    async fn receive_request(req: Request) -> Result<Response, DomeneshopError> {
+        assert_eq!(req.method(), Method::Get);
+        assert_url_equal(req.url(), "/domains?domain=.no");
+        assert_eq!(
+            req.header("Authorization").unwrap(),
+            "Basic dG9rZW46c2VjcmV0"
+        );
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let domains: Vec<Domain> = client
+        .request::<(), _>(Method::Get, "/domains", &[("domain", ".no")], None)
+        .await
+        .unwrap();
+
+    assert!(domains.is_empty());
+}
+
+#[tokio::test]
+async fn request_sends_json_body() {
+    async fn receive_request(mut req: Request) -> Result<Response, DomeneshopError> {
+        assert_eq!(req.method(), Method::Post);
+        assert_url_equal(req.url(), "/domains/1/renew");
+        assert_eq!(req.content_type().unwrap().essence(), "application/json");
+        assert_eq!(req.body_string().await.unwrap(), "{\"years\":2}");
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("{\"expiry_date\": \"2030-01-01\"}");
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let response: RenewalResponse = client
+        .request(
+            Method::Post,
+            "/domains/1/renew",
+            &[],
+            Some(&Renewal { years: 2 }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.expiry_date, "2030-01-01");
+}
+
+#[tokio::test]
+async fn request_accepts_empty_response() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        Ok(Response::new(StatusCode::NoContent))
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    client
+        .request::<(), ()>(Method::Delete, "/domains/1/renew", &[], None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn request_maps_api_errors() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::NotFound);
+        response.set_body("{\"code\": \"domain:not_found\", \"help\": \"Domain not found\"}");
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let err = client
+        .request::<(), ()>(Method::Put, "/domains/1/renew", &[], None)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err.kind(), DomeneshopErrorKind::Api { .. }));
+    assert_eq!(err.status(), Some(StatusCode::NotFound));
+}
+
+#[tokio::test]
+async fn request_raw_returns_response() {
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        let mut response = Response::new(StatusCode::Accepted);
+        response.insert_header("X-Job", "42");
+        response.set_body("queued");
+        Ok(response)
+    }
+
+    let client = create_client(MockClient {
+        req_received: receive_request,
+    });
+
+    let mut response = client
+        .request_raw::<()>(Method::Post, "/domains/1/jobs", &[], None)
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::Accepted);
+    assert_eq!(response.header("X-Job").unwrap(), "42");
+    assert_eq!(response.body_string().await.unwrap(), "queued");
+}
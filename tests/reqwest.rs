@@ -0,0 +1,196 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread::JoinHandle,
+};
+
+use domeneshop_client::{
+    blocking::BlockingHttpClient,
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    endpoints::dns::{DnsRecordData, TXTRecordData, Ttl},
+    http::HttpClient,
+};
+use http_types::{Method, Request, StatusCode, Url};
+
+/// Serves a single connection with the raw `response`, returning the raw request received
+fn serve_once(response: &'static str) -> (Url, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    let handle = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+
+        let mut stream = reader.into_inner();
+        stream.write_all(response.as_bytes()).unwrap();
+        stream.flush().unwrap();
+        request
+    });
+    (url, handle)
+}
+
+const CREATED: &str = "HTTP/1.1 201 Created\r\n\
+Content-Type: application/json\r\n\
+Location: https://api.domeneshop.no/v0/domains/1/dns/2\r\n\
+X-Request-Id: abc\r\n\
+Set-Cookie: a=1\r\n\
+Set-Cookie: b=2\r\n\
+Content-Length: 9\r\n\
+Connection: close\r\n\
+\r\n\
+{\"id\": 2}";
+
+const NO_CONTENT_TYPE: &str = "HTTP/1.1 200 OK\r\n\
+Content-Length: 3\r\n\
+Connection: close\r\n\
+\r\n\
+\x00\x01\x02";
+
+#[tokio::test]
+async fn response_headers_are_preserved() {
+    let (url, server) = serve_once(CREATED);
+
+    let mut response = reqwest::Client::new()
+        .execute_request(Request::new(Method::Get, url))
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(response.status(), StatusCode::Created);
+    assert_eq!(
+        response.header("Location").unwrap().as_str(),
+        "https://api.domeneshop.no/v0/domains/1/dns/2"
+    );
+    assert_eq!(response.header("X-Request-Id").unwrap().as_str(), "abc");
+    let cookies: Vec<_> = response
+        .header("Set-Cookie")
+        .unwrap()
+        .iter()
+        .map(|value| value.as_str().to_string())
+        .collect();
+    assert_eq!(cookies, vec!["a=1", "b=2"]);
+    assert_eq!(
+        response.content_type().unwrap().essence(),
+        "application/json"
+    );
+    assert_eq!(response.body_string().await.unwrap(), "{\"id\": 2}");
+}
+
+#[tokio::test]
+async fn body_is_kept_as_bytes() {
+    let (url, server) = serve_once(NO_CONTENT_TYPE);
+
+    let mut response = reqwest::Client::new()
+        .execute_request(Request::new(Method::Get, url))
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert!(response.header("Content-Type").is_none());
+    assert_eq!(response.body_bytes().await.unwrap(), vec![0, 1, 2]);
+}
+
+#[tokio::test]
+async fn request_method_headers_and_body_are_sent() {
+    let (url, server) = serve_once(NO_CONTENT_TYPE);
+    let mut request = Request::new(Method::Patch, url);
+    request.append_header("X-Custom", "one");
+    request.append_header("X-Custom", "two");
+    request.set_body("payload");
+
+    reqwest::Client::new()
+        .execute_request(request)
+        .await
+        .unwrap();
+    let received = server.join().unwrap().to_lowercase();
+
+    assert!(received.starts_with("patch / http/1.1"));
+    assert!(received.contains("x-custom: one\r\n"));
+    assert!(received.contains("x-custom: two\r\n"));
+    assert!(received.ends_with("\r\n\r\npayload"));
+}
+
+#[tokio::test]
+async fn added_dns_record_has_url() {
+    let (url, server) = serve_once(CREATED);
+    let client = DomeneshopClient::new(
+        String::from("token"),
+        String::from("secret"),
+        DomeneshopClientConfiguration {
+            base_url: Some(url.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let added = client
+        .add_dns_record(
+            1,
+            DnsRecordData::TXT(TXTRecordData {
+                host: "@".to_string(),
+                ttl: Ttl::HOUR,
+                data: "hello".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(added.id, 2);
+    assert_eq!(
+        added.url.unwrap().as_str(),
+        "https://api.domeneshop.no/v0/domains/1/dns/2"
+    );
+}
+
+#[test]
+fn blocking_response_headers_are_preserved() {
+    let (url, server) = serve_once(CREATED);
+
+    let mut response = reqwest::blocking::Client::new()
+        .execute_request(Request::new(Method::Get, url))
+        .unwrap();
+    server.join().unwrap();
+
+    assert_eq!(response.status(), StatusCode::Created);
+    assert_eq!(response.header("X-Request-Id").unwrap().as_str(), "abc");
+    assert_eq!(response.header("Set-Cookie").unwrap().iter().count(), 2);
+    assert_eq!(read_body(&mut response), b"{\"id\": 2}".to_vec());
+}
+
+#[test]
+fn blocking_request_method_and_body_are_sent() {
+    let (url, server) = serve_once(NO_CONTENT_TYPE);
+    let mut request = Request::new(Method::Options, url);
+    request.set_body("payload");
+
+    let mut response = reqwest::blocking::Client::new()
+        .execute_request(request)
+        .unwrap();
+    let received = server.join().unwrap().to_lowercase();
+
+    assert!(received.starts_with("options / http/1.1"));
+    assert!(received.ends_with("\r\n\r\npayload"));
+    assert_eq!(read_body(&mut response), vec![0, 1, 2]);
+}
+
+fn read_body(response: &mut http_types::Response) -> Vec<u8> {
+    tokio_test::block_on(response.body_bytes()).unwrap()
+}
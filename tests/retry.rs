@@ -0,0 +1,249 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::forwards::HttpForward,
+    errors::DomeneshopError,
+    http_client::mock::MockClient,
+    retry::RetryPolicy,
+};
+use http_types::{Request, Response, StatusCode};
+use url::Url;
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client<F>(
+    http_client: MockClient<F>,
+    retry_policy: RetryPolicy,
+) -> DomeneshopClient<MockClient<F>>
+where
+    F: std::future::Future<Output = Result<Response, DomeneshopError>> + Send + 'static,
+{
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        http_client,
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            retry_policy,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::ZERO,
+        ..RetryPolicy::default()
+    }
+}
+
+fn forward() -> HttpForward {
+    HttpForward {
+        frame: false,
+        host: "www".to_string(),
+        url: Url::parse("https://example.com").unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn get_is_retried_on_bad_gateway() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Ok(Response::new(StatusCode::BadGateway));
+        }
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        fast_policy(),
+    );
+
+    let response = client.list_forwards(3).await.unwrap();
+
+    assert_eq!(0, response.len());
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn get_is_retried_on_transport_error() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(DomeneshopError::transport(
+                "Connection reset",
+                std::io::Error::from(std::io::ErrorKind::ConnectionReset),
+            ));
+        }
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        fast_policy(),
+    );
+
+    client.list_forwards(3).await.unwrap();
+
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn retries_stop_after_max_attempts() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(Response::new(StatusCode::ServiceUnavailable))
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        fast_policy(),
+    );
+
+    let error = client.list_forwards(3).await.unwrap_err();
+
+    assert_eq!(error.status(), Some(StatusCode::ServiceUnavailable));
+    assert_eq!(3, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn non_retryable_status_is_not_retried() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(Response::new(StatusCode::NotFound))
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        fast_policy(),
+    );
+
+    client
+        .delete_forward(3, "www".to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(1, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn post_is_not_retried_by_default() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(Response::new(StatusCode::BadGateway))
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        fast_policy(),
+    );
+
+    client.add_forward(3, forward()).await.unwrap_err();
+
+    assert_eq!(1, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn post_is_retried_with_same_body_when_enabled() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(mut req: Request) -> Result<Response, DomeneshopError> {
+        let json = req.body_string().await.unwrap();
+        assert_eq!(
+            json,
+            "{\"host\":\"www\",\"frame\":false,\"url\":\"https://example.com/\"}"
+        );
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Ok(Response::new(StatusCode::BadGateway));
+        }
+        Ok(Response::new(StatusCode::Created))
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        RetryPolicy {
+            retry_non_idempotent: true,
+            ..fast_policy()
+        },
+    );
+
+    client.add_forward(3, forward()).await.unwrap();
+
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn disabled_policy_does_not_retry() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(Response::new(StatusCode::BadGateway))
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        RetryPolicy::disabled(),
+    );
+
+    client.list_forwards(3).await.unwrap_err();
+
+    assert_eq!(1, CALLS.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn retry_after_is_capped_by_max_backoff() {
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    async fn receive_request(_: Request) -> Result<Response, DomeneshopError> {
+        if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+            let mut response = Response::new(StatusCode::ServiceUnavailable);
+            response.insert_header("Retry-After", "3600");
+            return Ok(response);
+        }
+        let mut response = Response::new(StatusCode::Ok);
+        response.set_body("[]");
+        Ok(response)
+    }
+
+    let client = create_client(
+        MockClient {
+            req_received: receive_request,
+        },
+        RetryPolicy {
+            max_backoff: Duration::from_millis(10),
+            ..fast_policy()
+        },
+    );
+
+    let started = std::time::Instant::now();
+    client.list_forwards(3).await.unwrap();
+
+    assert_eq!(2, CALLS.load(Ordering::SeqCst));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    net::{IpAddr, Ipv4Addr},
+    sync::{Arc, Mutex},
+};
+
+use domeneshop_client::{
+    endpoints::{
+        dns::{CNAMERecordData, DnsRecordData, ExistingDnsRecord, Ttl},
+        forwards::HttpForward,
+        invoices::InvoiceStatus,
+    },
+    errors::DomeneshopError,
+    http_client::mock::MockClient,
+};
+use http_types::{Method, Request, Response, StatusCode};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+use url::Url;
+
+use crate::common::create_client;
+mod common;
+
+#[derive(Debug, Default, Clone)]
+struct CapturedSpan {
+    name: String,
+    fields: HashMap<String, String>,
+    events: Vec<HashMap<String, String>>,
+}
+
+#[derive(Clone, Default)]
+struct CaptureLayer {
+    spans: Arc<Mutex<HashMap<u64, CapturedSpan>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl<S> Layer<S> for CaptureLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, _: Context<'_, S>) {
+        let mut span = CapturedSpan {
+            name: attrs.metadata().name().to_string(),
+            ..CapturedSpan::default()
+        };
+        attrs.record(&mut FieldVisitor(&mut span.fields));
+        self.spans.lock().unwrap().insert(id.into_u64(), span);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, _: Context<'_, S>) {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(&mut FieldVisitor(&mut span.fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if let Some(current) = ctx.lookup_current() {
+            let mut fields = HashMap::new();
+            event.record(&mut FieldVisitor(&mut fields));
+            if let Some(span) = self.spans.lock().unwrap().get_mut(&current.id().into_u64()) {
+                span.events.push(fields);
+            }
+        }
+    }
+}
+
+impl CaptureLayer {
+    fn span(&self, name: &str) -> CapturedSpan {
+        self.spans
+            .lock()
+            .unwrap()
+            .values()
+            .find(|span| span.name == name)
+            .unwrap_or_else(|| panic!("No span named {}", name))
+            .clone()
+    }
+
+    fn all_values(&self) -> Vec<String> {
+        self.spans
+            .lock()
+            .unwrap()
+            .values()
+            .flat_map(|span| {
+                span.fields
+                    .values()
+                    .chain(span.events.iter().flat_map(|event| event.values()))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+const DOMAIN: &str = "{ \"domain\": \"d\", \"registrant\": \"d\", \"status\": \"active\", \"expiry_date\": \"2023-04-05\", \"id\": 3, \"renew\": false, \"nameservers\": [], \"services\": { \"registrar\": false, \"dns\": false, \"email\": false, \"webhotel\": \"none\" } }";
+const DNS_RECORD: &str =
+    "{\"id\": 2, \"host\":\"t\", \"ttl\": 3600, \"type\": \"A\", \"data\": \"192.168.0.1\"}";
+const FORWARD: &str = "{\"host\":\"www\", \"frame\": false, \"url\": \"https://example.com\"}";
+const INVOICE: &str = "{\"id\": 1, \"type\": \"invoice\", \"amount\": 120, \"currency\": \"NOK\", \"due_date\": \"2019-08-24\", \"issued_date\": \"2019-08-24\", \"paid_date\": \"2019-08-24\", \"status\": \"paid\", \"url\": \"https://www.domeneshop.no/invoice?nr=1&code=\"}";
+
+async fn route(req: Request) -> Result<Response, DomeneshopError> {
+    let path = req.url().path().trim_start_matches("/v0").to_string();
+    let segments: Vec<_> = path.split('/').skip(1).collect();
+    let (status, body) = match (req.method(), segments.as_slice()) {
+        (Method::Get, ["domains"]) => (StatusCode::Ok, format!("[{}]", DOMAIN)),
+        (Method::Get, ["domains", _]) => (StatusCode::Ok, DOMAIN.to_string()),
+        (Method::Get, ["domains", _, "dns"]) => (StatusCode::Ok, format!("[{}]", DNS_RECORD)),
+        (Method::Get, ["domains", _, "dns", _]) => (StatusCode::Ok, DNS_RECORD.to_string()),
+        (Method::Post, ["domains", _, "dns"]) => (StatusCode::Created, "{\"id\": 2}".to_string()),
+        (Method::Put, ["domains", _, "dns", _]) => (StatusCode::NoContent, String::new()),
+        (Method::Delete, ["domains", _, "dns", "404"]) => (StatusCode::NotFound, String::new()),
+        (Method::Delete, ["domains", _, "dns", _]) => (StatusCode::NoContent, String::new()),
+        (Method::Get, ["domains", _, "forwards"]) => (StatusCode::Ok, format!("[{}]", FORWARD)),
+        (Method::Get, ["domains", _, "forwards", _]) => (StatusCode::Ok, FORWARD.to_string()),
+        (Method::Post, ["domains", _, "forwards"]) => (StatusCode::Created, FORWARD.to_string()),
+        (Method::Put, ["domains", _, "forwards", _]) => (StatusCode::Ok, FORWARD.to_string()),
+        (Method::Delete, ["domains", _, "forwards", _]) => (StatusCode::NoContent, String::new()),
+        (Method::Get, ["invoices"]) => (StatusCode::Ok, format!("[{}]", INVOICE)),
+        (Method::Get, ["invoices", _]) => (StatusCode::Ok, INVOICE.to_string()),
+        (Method::Get, ["dyndns", "update"]) => (StatusCode::NoContent, String::new()),
+        _ => (StatusCode::NotFound, String::new()),
+    };
+    let mut response = Response::new(status);
+    if !body.is_empty() {
+        response.set_body(body);
+    }
+    Ok(response)
+}
+
+fn forward() -> HttpForward {
+    HttpForward {
+        frame: false,
+        host: "www".to_string(),
+        url: Url::parse("https://example.com").unwrap(),
+    }
+}
+
+fn record() -> DnsRecordData {
+    DnsRecordData::CNAME(CNAMERecordData {
+        host: "t".to_string(),
+        ttl: Ttl::HOUR,
+        data: "a".to_string(),
+    })
+}
+
+#[tokio::test]
+async fn every_endpoint_emits_a_span() {
+    let layer = CaptureLayer::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+    let client = create_client(MockClient {
+        req_received: route,
+    });
+
+    client.get_domain(3).await.unwrap();
+    client.list_domains().await.unwrap();
+    client.list_domains_with_filter(".no").await.unwrap();
+    client.get_dns_record(3, 2).await.unwrap();
+    client.list_dns_records(3).await.unwrap();
+    client
+        .list_dns_records_with_filter(3, Some("t".to_string()), None)
+        .await
+        .unwrap();
+    client.add_dns_record(3, record()).await.unwrap();
+    client
+        .update_dns_record(
+            3,
+            ExistingDnsRecord {
+                id: 2,
+                data: record(),
+            },
+        )
+        .await
+        .unwrap();
+    client.delete_dns_record(3, 2).await.unwrap();
+    client.get_forward(3, "www".to_string()).await.unwrap();
+    client.list_forwards(3).await.unwrap();
+    client.add_forward(3, forward()).await.unwrap();
+    client.update_forward(3, forward()).await.unwrap();
+    client.delete_forward(3, "www".to_string()).await.unwrap();
+    client.get_invoice(1).await.unwrap();
+    client.list_invoices().await.unwrap();
+    client
+        .list_invoices_with_status(InvoiceStatus::Paid)
+        .await
+        .unwrap();
+    client
+        .update_dyndns("www.example.com", Some(IpAddr::V4(Ipv4Addr::LOCALHOST)))
+        .await
+        .unwrap();
+
+    for name in [
+        "get_domain",
+        "list_domains",
+        "list_domains_with_filter",
+        "get_dns_record",
+        "list_dns_records",
+        "list_dns_records_with_filter",
+        "add_dns_record",
+        "update_dns_record",
+        "delete_dns_record",
+        "get_forward",
+        "list_forwards",
+        "add_forward",
+        "update_forward",
+        "delete_forward",
+        "get_invoice",
+        "list_invoices",
+        "list_invoices_with_status",
+        "update_dyndns",
+    ] {
+        let span = layer.span(name);
+        for field in ["http.method", "url.path", "http.status_code", "latency_ms"] {
+            assert!(
+                span.fields.contains_key(field),
+                "Span {} is missing field {}",
+                name,
+                field
+            );
+        }
+    }
+
+    let span = layer.span("update_dns_record");
+    assert_eq!(span.fields["domain_id"], "3");
+    assert_eq!(span.fields["dns_id"], "2");
+    assert_eq!(span.fields["http.method"], "PUT");
+    assert_eq!(span.fields["url.path"], "/v0/domains/3/dns/2");
+    assert_eq!(span.fields["http.status_code"], "204");
+    assert_eq!(layer.span("list_forwards").fields["domain_id"], "3");
+}
+
+#[tokio::test]
+async fn errors_are_recorded_as_events() {
+    let layer = CaptureLayer::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+    let client = create_client(MockClient {
+        req_received: route,
+    });
+
+    client.delete_dns_record(3, 404).await.unwrap_err();
+
+    let span = layer.span("delete_dns_record");
+    assert_eq!(span.fields["http.status_code"], "404");
+    assert_eq!(span.events.len(), 1);
+    assert!(span.events[0]["error"].contains("404"));
+}
+
+#[tokio::test]
+async fn credentials_are_never_recorded() {
+    let layer = CaptureLayer::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+    let client = create_client(MockClient {
+        req_received: route,
+    });
+
+    client.list_domains().await.unwrap();
+    client.add_dns_record(3, record()).await.unwrap();
+    client.delete_dns_record(3, 404).await.unwrap_err();
+
+    let values = layer.all_values();
+    assert!(!values.is_empty());
+    for value in values {
+        assert!(!value.contains("secret"));
+        assert!(!value.contains("dG9rZW46c2VjcmV0"));
+    }
+}
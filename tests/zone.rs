@@ -0,0 +1,168 @@
+use domeneshop_client::{
+    client::{DomeneshopClient, DomeneshopClientConfiguration},
+    credentials::Credentials,
+    endpoints::dns::{ARecordData, CNAMERecordData, DnsRecordData, MXRecordData, Ttl},
+    errors::DomeneshopErrorKind,
+    http_client::fake::{self, FakeDomeneshop},
+    zone::{parse_zone, ImportAction, ImportOutcome},
+};
+
+use crate::common::TEST_BASE_URL;
+mod common;
+
+fn create_client(fake: &FakeDomeneshop) -> DomeneshopClient<FakeDomeneshop> {
+    DomeneshopClient::with_http_client(
+        Credentials::new("token", "secret"),
+        fake.clone(),
+        DomeneshopClientConfiguration {
+            base_url: Some(TEST_BASE_URL.to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn export_zone_renders_records_of_domain() {
+    let fake = FakeDomeneshop::new();
+    fake.add_domain(fake::domain(1, "example.com"));
+    fake.add_domain(fake::domain(2, "example.no"));
+    fake.add_dns_record(
+        1,
+        DnsRecordData::CNAME(CNAMERecordData {
+            host: "www".to_string(),
+            ttl: Ttl::HOUR,
+            data: "example.com".to_string(),
+        }),
+    );
+    fake.add_dns_record(
+        1,
+        DnsRecordData::MX(MXRecordData {
+            host: "@".to_string(),
+            ttl: Ttl::DAY,
+            data: "mx.example.net".to_string(),
+            priority: 10,
+        }),
+    );
+    fake.add_dns_record(
+        1,
+        DnsRecordData::A(ARecordData {
+            host: "@".to_string(),
+            ttl: Ttl::HOUR,
+            data: "192.0.2.1".parse().unwrap(),
+        }),
+    );
+    fake.add_dns_record(
+        2,
+        DnsRecordData::A(ARecordData {
+            host: "@".to_string(),
+            ttl: Ttl::HOUR,
+            data: "192.0.2.2".parse().unwrap(),
+        }),
+    );
+    let client = create_client(&fake);
+
+    let zone = client.export_zone(1).await.unwrap();
+
+    assert_eq!(
+        zone,
+        "$ORIGIN example.com.\n\
+         $TTL 3600\n\
+         @         IN A     192.0.2.1\n\
+         @   86400 IN MX    10 mx.example.net.\n\
+         www       IN CNAME example.com.\n"
+    );
+}
+
+#[tokio::test]
+async fn export_zone_of_unknown_domain_fails() {
+    let fake = FakeDomeneshop::new();
+    let client = create_client(&fake);
+
+    assert!(client.export_zone(1).await.is_err());
+}
+
+#[tokio::test]
+async fn zone_file_is_previewed_and_imported() {
+    let fake = FakeDomeneshop::new();
+    fake.add_domain(fake::domain(1, "example.com"));
+    let existing = fake.add_dns_record(
+        1,
+        DnsRecordData::A(ARecordData {
+            host: "@".to_string(),
+            ttl: Ttl::HOUR,
+            data: "192.0.2.1".parse().unwrap(),
+        }),
+    );
+    let client = create_client(&fake);
+    let zone = parse_zone(
+        &format!(
+            "$ORIGIN example.com.\n\
+             $TTL 1h\n\
+             @    IN SOA ns1.hyp.net. hostmaster.example.com. ( 1 3h 1h 1w 1h )\n\
+             \x20    IN A     192.0.2.1\n\
+             www  IN CNAME @\n\
+             blog IN CNAME www\n\
+             blog IN A     192.0.2.2 ; collides with the CNAME\n\
+             long IN TXT   {}\n\
+             odd  90 IN A  192.0.2.3 ; not a whole number of minutes\n",
+            "a".repeat(256)
+        ),
+        "example.com",
+    )
+    .unwrap();
+
+    let plan = client.preview_zone_import(1, zone.records).await.unwrap();
+
+    let actions: Vec<_> = plan
+        .records
+        .iter()
+        .map(|planned| match &planned.action {
+            ImportAction::Invalid(_) => "invalid".to_string(),
+            action => format!("{:?}", action),
+        })
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            format!("AlreadyExists({})", existing),
+            "Create".to_string(),
+            "Create".to_string(),
+            "Create".to_string(),
+            "invalid".to_string(),
+            "invalid".to_string()
+        ]
+    );
+    assert_eq!(plan.to_create().count(), 3);
+    assert_eq!(zone.unsupported[0].dns_type, "SOA");
+    assert_eq!(fake.dns_records(1).len(), 1);
+
+    let report = client.import_zone(plan).await;
+
+    assert!(!report.is_success());
+    assert!(matches!(
+        report.results[1].outcome,
+        ImportOutcome::Created(_)
+    ));
+    assert!(matches!(
+        report.results[2].outcome,
+        ImportOutcome::Created(_)
+    ));
+    match &report.results[3].outcome {
+        ImportOutcome::Failed(err) => assert!(matches!(
+            err.kind(),
+            DomeneshopErrorKind::Api { code, .. } if code == "record:collision"
+        )),
+        outcome => panic!("Wrong outcome {:?}", outcome),
+    }
+    assert_eq!(report.failures().count(), 3);
+    assert_eq!(fake.dns_records(1).len(), 3);
+    assert_eq!(
+        client.export_zone(1).await.unwrap(),
+        "$ORIGIN example.com.\n\
+         $TTL 3600\n\
+         @    IN A     192.0.2.1\n\
+         blog IN CNAME www.example.com.\n\
+         www  IN CNAME example.com.\n"
+    );
+}